@@ -0,0 +1,352 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{ hash::hash, instruction::{ AccountMeta, Instruction } },
+    AccountDeserialize,
+};
+use anchor_spl::{ associated_token::get_associated_token_address, token::{ spl_token, TokenAccount } };
+use anyhow::{ Context, Result };
+use rust_decimal::Decimal;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{ read_keypair_file, Keypair, Signer },
+    transaction::Transaction,
+};
+use vyper_otc_client::{
+    find_otc_authority,
+    rpc::{ fetch_all_otc_states, fetch_global_config, fetch_otc_accounts },
+    InitializeAccounts, InitializeInputData, OtcAccounts, OtcState, VyperCoreAccounts,
+};
+
+use crate::{ terms::{ required, Terms }, Side };
+
+const DEFAULT_TRANCHE_MINT_DECIMALS: u8 = 6;
+
+pub struct Config {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+}
+
+/// reads a keypair file, expanding a leading `~/`
+fn read_keypair(keypair_path: &str) -> Result<Keypair> {
+    let keypair_path = match keypair_path.strip_prefix("~/") {
+        Some(path) => format!("{}/{}", std::env::var("HOME").context("HOME not set")?, path),
+        None => keypair_path.to_string(),
+    };
+    read_keypair_file(&keypair_path).map_err(|err| anyhow::anyhow!("reading keypair {}: {}", keypair_path, err))
+}
+
+pub fn rpc_client(url: &str) -> RpcClient {
+    RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed())
+}
+
+impl Config {
+    pub fn new(url: &str, keypair_path: &str) -> Result<Self> {
+        Ok(Self {
+            rpc: rpc_client(url),
+            payer: read_keypair(keypair_path)?,
+        })
+    }
+
+    fn send(&self, ixs: &[Instruction], signers: &[&Keypair]) -> Result<()> {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            self.rpc.get_latest_blockhash()?,
+        );
+        let signature = self.rpc.send_and_confirm_transaction(&tx)?;
+        println!("tx: {}", signature);
+        Ok(())
+    }
+
+    fn reserve_token_account(&self, reserve_mint: &Pubkey, beneficiary: Option<Pubkey>) -> Pubkey {
+        beneficiary.unwrap_or_else(|| get_associated_token_address(&self.payer.pubkey(), reserve_mint))
+    }
+
+    /// creates the signer associated token account if missing, native SOL users may not have one
+    fn create_token_account_ixs(&self, mint: &Pubkey) -> Vec<Instruction> {
+        let token_account = get_associated_token_address(&self.payer.pubkey(), mint);
+        if self.rpc.get_account(&token_account).is_ok() {
+            return vec![];
+        }
+        vec![spl_associated_token_account::create_associated_token_account(
+            &self.payer.pubkey(),
+            &self.payer.pubkey(),
+            mint,
+        )]
+    }
+}
+
+/// native SOL otcs are wrapped on deposit and unwrapped when paying the signer
+fn is_native(reserve_mint: &Pubkey) -> bool {
+    *reserve_mint == spl_token::native_mint::ID
+}
+
+// - - - - - - - - - - - -
+// Lifecycle
+
+pub fn create(config: &Config, terms: Terms) -> Result<()> {
+    let reserve_mint = required(terms.reserve_mint, "reserve_mint")?;
+
+    let otc_state = Keypair::new();
+    let otc_authority = find_otc_authority(&otc_state.pubkey()).0;
+
+    // vyper tranche configuration owned by the otc authority,
+    // deposits and redeems restricted to the owner
+    let tranche_config = Keypair::new();
+    let senior_tranche_mint = Keypair::new();
+    let junior_tranche_mint = Keypair::new();
+    let vyper = VyperCoreAccounts::new(
+        tranche_config.pubkey(),
+        reserve_mint,
+        senior_tranche_mint.pubkey(),
+        junior_tranche_mint.pubkey(),
+        required(terms.rate_program, "rate_program")?,
+        required(terms.rate_program_state, "rate_program_state")?,
+        required(terms.redeem_logic_program, "redeem_logic_program")?,
+        required(terms.redeem_logic_program_state, "redeem_logic_program_state")?,
+    );
+    let vyper_init_ix = vyper_otc_client::initialize_vyper_tranche_config(
+        &config.payer.pubkey(),
+        &otc_authority,
+        &vyper,
+        terms.tranche_mint_decimals.unwrap_or(DEFAULT_TRANCHE_MINT_DECIMALS),
+    );
+    config.send(&[vyper_init_ix], &[&tranche_config, &senior_tranche_mint, &junior_tranche_mint])?;
+    println!("vyper tranche config: {}", vyper.tranche_config);
+
+    // otc on top of the tranche configuration
+    let otc_senior_reserve_token_account = Keypair::new();
+    let otc_junior_reserve_token_account = Keypair::new();
+    let otc_senior_tranche_token_account = Keypair::new();
+    let otc_junior_tranche_token_account = Keypair::new();
+    let init_ix = vyper_otc_client::initialize(
+        &config.payer.pubkey(),
+        &InitializeAccounts {
+            otc_state: otc_state.pubkey(),
+            otc_senior_reserve_token_account: otc_senior_reserve_token_account.pubkey(),
+            otc_junior_reserve_token_account: otc_junior_reserve_token_account.pubkey(),
+            otc_senior_tranche_token_account: otc_senior_tranche_token_account.pubkey(),
+            otc_junior_tranche_token_account: otc_junior_tranche_token_account.pubkey(),
+        },
+        &vyper,
+        InitializeInputData {
+            senior_deposit_amount: required(terms.senior_deposit_amount, "senior_deposit_amount")?,
+            junior_deposit_amount: required(terms.junior_deposit_amount, "junior_deposit_amount")?,
+            deposit_start: terms.deposit_start,
+            deposit_end: required(terms.deposit_end, "deposit_end")?,
+            settle_start: required(terms.settle_start, "settle_start")?,
+            settle_deadline: terms.settle_deadline,
+            senior_counterparty: terms.senior_counterparty,
+            junior_counterparty: terms.junior_counterparty,
+            settle_bounty: terms.settle_bounty.unwrap_or(0),
+        },
+    );
+    config.send(
+        &[init_ix],
+        &[
+            &otc_state,
+            &otc_senior_reserve_token_account,
+            &otc_junior_reserve_token_account,
+            &otc_senior_tranche_token_account,
+            &otc_junior_tranche_token_account,
+        ],
+    )?;
+    println!("otc state: {}", otc_state.pubkey());
+
+    Ok(())
+}
+
+pub fn deposit(config: &Config, otc_state: &Pubkey, side: Side, beneficiary: Option<Pubkey>) -> Result<()> {
+    let otc = fetch_otc_accounts(&config.rpc, otc_state)?;
+    let global_config = fetch_global_config(&config.rpc)?;
+    let native = is_native(&otc.vyper.reserve_mint);
+    // native SOL is wrapped in the signer associated token account before the deposit
+    let mut ixs = if native {
+        config.create_token_account_ixs(&otc.vyper.reserve_mint)
+    } else {
+        vec![]
+    };
+    let user_reserve_token_account = config.reserve_token_account(&otc.vyper.reserve_mint, None);
+    // the beneficiary token account owner receives the position token
+    let beneficiary_owner = match beneficiary {
+        Some(beneficiary) => TokenAccount::try_deserialize(&mut config.rpc.get_account_data(&beneficiary)?.as_slice())?.owner,
+        None => config.payer.pubkey(),
+    };
+    ixs.push(otc.deposit(
+        &config.payer.pubkey(),
+        &user_reserve_token_account,
+        &config.reserve_token_account(&otc.vyper.reserve_mint, beneficiary),
+        &beneficiary_owner,
+        &get_associated_token_address(&global_config.treasury, &otc.vyper.reserve_mint),
+        side.is_senior(),
+        native,
+    ));
+    config.send(&ixs, &[])
+}
+
+pub fn withdraw(config: &Config, otc_state: &Pubkey, side: Side, beneficiary: Option<Pubkey>) -> Result<()> {
+    let otc = fetch_otc_accounts(&config.rpc, otc_state)?;
+    let unwrap_native = is_native(&otc.vyper.reserve_mint) && beneficiary.is_none();
+    let mut ixs = if unwrap_native {
+        config.create_token_account_ixs(&otc.vyper.reserve_mint)
+    } else {
+        vec![]
+    };
+    ixs.push(otc.withdraw(
+        &config.payer.pubkey(),
+        &config.reserve_token_account(&otc.vyper.reserve_mint, None),
+        &config.reserve_token_account(&otc.vyper.reserve_mint, beneficiary),
+        side.is_senior(),
+        unwrap_native,
+    ));
+    config.send(&ixs, &[])
+}
+
+/// rate plugins expose a `refresh` instruction on their state, plus plugin specific remaining accounts
+fn rate_refresh_ix(otc: &OtcAccounts, rate_refresh_accounts: &[Pubkey]) -> Instruction {
+    let mut rate_refresh_metas = vec![AccountMeta::new(otc.vyper.rate_program_state, false)];
+    rate_refresh_metas.extend(rate_refresh_accounts.iter().map(|account| AccountMeta::new_readonly(*account, false)));
+    Instruction {
+        program_id: otc.vyper.rate_program,
+        accounts: rate_refresh_metas,
+        data: hash(b"global:refresh").to_bytes()[..8].to_vec(),
+    }
+}
+
+pub fn settle(config: &Config, otc_state: &Pubkey, rate_refresh_accounts: &[Pubkey]) -> Result<()> {
+    let otc = fetch_otc_accounts(&config.rpc, otc_state)?;
+    config.send(
+        &[rate_refresh_ix(&otc, rate_refresh_accounts), otc.refresh_tranche_fair_value(), otc.settle(&config.payer.pubkey())],
+        &[],
+    )
+}
+
+pub fn early_terminate(
+    config: &Config,
+    otc_state: &Pubkey,
+    side: Side,
+    counterparty_keypair: &str,
+    senior_split_bps: Option<u16>,
+    rate_refresh_accounts: &[Pubkey],
+) -> Result<()> {
+    let otc = fetch_otc_accounts(&config.rpc, otc_state)?;
+    let counterparty = read_keypair(counterparty_keypair)?;
+    let (senior_signer, junior_signer) = if side.is_senior() {
+        (config.payer.pubkey(), counterparty.pubkey())
+    } else {
+        (counterparty.pubkey(), config.payer.pubkey())
+    };
+    let mut ixs = vec![];
+    if senior_split_bps.is_none() {
+        ixs.push(rate_refresh_ix(&otc, rate_refresh_accounts));
+        ixs.push(otc.refresh_tranche_fair_value());
+    }
+    ixs.push(otc.early_terminate(&senior_signer, &junior_signer, senior_split_bps));
+    config.send(&ixs, &[&counterparty])
+}
+
+pub fn unwind(config: &Config, otc_state: &Pubkey) -> Result<()> {
+    let otc = fetch_otc_accounts(&config.rpc, otc_state)?;
+    config.send(&[otc.unwind(&config.payer.pubkey())], &[])
+}
+
+pub fn claim(config: &Config, otc_state: &Pubkey, side: Side, beneficiary: Option<Pubkey>) -> Result<()> {
+    let otc = fetch_otc_accounts(&config.rpc, otc_state)?;
+    let unwrap_native = is_native(&otc.vyper.reserve_mint) && beneficiary.is_none();
+    let mut ixs = if unwrap_native {
+        config.create_token_account_ixs(&otc.vyper.reserve_mint)
+    } else {
+        vec![]
+    };
+    ixs.push(otc.claim(
+        &config.payer.pubkey(),
+        &config.reserve_token_account(&otc.vyper.reserve_mint, beneficiary),
+        side.is_senior(),
+        unwrap_native,
+    ));
+    config.send(&ixs, &[])
+}
+
+pub fn distribute(config: &Config, otc_state: &Pubkey) -> Result<()> {
+    let otc = fetch_otc_accounts(&config.rpc, otc_state)?;
+    let mut ixs = vec![];
+    for (is_senior_side, beneficiary, claimed_amount) in [
+        (true, otc.otc_state.senior_side_beneficiary, otc.otc_state.senior_claimed_amount),
+        (false, otc.otc_state.junior_side_beneficiary, otc.otc_state.junior_claimed_amount),
+    ] {
+        let beneficiary = match (beneficiary, claimed_amount) {
+            (Some(beneficiary), None) => beneficiary,
+            _ => continue,
+        };
+        let beneficiary_token_account = TokenAccount::try_deserialize(&mut config.rpc.get_account_data(&beneficiary)?.as_slice())?;
+        ixs.extend(otc.distribute(&config.payer.pubkey(), &beneficiary_token_account.owner, is_senior_side));
+    }
+    anyhow::ensure!(!ixs.is_empty(), "no side left to distribute");
+    config.send(&ixs, &[])
+}
+
+pub fn close(config: &Config, otc_state: &Pubkey) -> Result<()> {
+    let otc = fetch_otc_accounts(&config.rpc, otc_state)?;
+    config.send(&[otc.close(&config.payer.pubkey())], &[])
+}
+
+// - - - - - - - - - - - -
+// Read
+
+pub fn show(rpc: &RpcClient, otc_state: &Pubkey) -> Result<()> {
+    let otc = fetch_otc_accounts(rpc, otc_state)?;
+    print_otc_state(otc_state, &otc.otc_state);
+    println!("vyper tranche config: {}", otc.vyper.tranche_config);
+    println!("reserve mint: {}", otc.vyper.reserve_mint);
+    println!("rate program: {}", otc.vyper.rate_program);
+    println!("redeem logic program: {}", otc.vyper.redeem_logic_program);
+    Ok(())
+}
+
+pub fn list(rpc: &RpcClient) -> Result<()> {
+    let otc_states = fetch_all_otc_states(rpc)?;
+    println!("otc states: {}", otc_states.len());
+    for (key, otc_state) in otc_states {
+        println!("+ {} {:?} settle start: {}", key, otc_state.status, otc_state.settle_start);
+    }
+    Ok(())
+}
+
+fn print_otc_state(key: &Pubkey, otc_state: &OtcState) {
+    let fmt_opt = |value: Option<Pubkey>| value.map_or_else(|| "-".to_string(), |value| value.to_string());
+    println!("otc state: {}", key);
+    println!("status: {:?}", otc_state.status);
+    println!("creator: {}", otc_state.creator);
+    println!("deposit start: {}", otc_state.deposit_start);
+    println!("deposit end: {}", otc_state.deposit_end);
+    println!("settle start: {}", otc_state.settle_start);
+    println!("settle deadline: {}", otc_state.settle_deadline);
+    println!("fee bps: {}", otc_state.fee_bps);
+    println!("settle bounty: {} lamports", otc_state.settle_bounty);
+    println!("senior deposit amount: {}", otc_state.senior_deposit_amount);
+    println!("junior deposit amount: {}", otc_state.junior_deposit_amount);
+    println!("senior side beneficiary: {}", fmt_opt(otc_state.senior_side_beneficiary));
+    println!("junior side beneficiary: {}", fmt_opt(otc_state.junior_side_beneficiary));
+    println!("senior counterparty: {}", fmt_opt(otc_state.senior_counterparty));
+    println!("junior counterparty: {}", fmt_opt(otc_state.junior_counterparty));
+    println!("senior redeemed amount: {}", otc_state.senior_redeemed_amount);
+    println!("junior redeemed amount: {}", otc_state.junior_redeemed_amount);
+    if let Some(settle_timestamp) = otc_state.settle_timestamp {
+        let fmt_decimals = |values: &[[u8; 16]]| {
+            values.iter().map(|value| Decimal::deserialize(*value).to_string()).collect::<Vec<_>>().join(", ")
+        };
+        println!("settle timestamp: {}", settle_timestamp);
+        println!("settle reserve fair value: [{}]", fmt_decimals(&otc_state.settle_reserve_fair_value));
+        println!("settle tranche fair value: [{}]", fmt_decimals(&otc_state.settle_tranche_fair_value));
+    }
+    println!("senior position mint: {}", otc_state.senior_position_mint);
+    println!("junior position mint: {}", otc_state.junior_position_mint);
+    println!("otc senior reserve token account: {}", otc_state.otc_senior_reserve_token_account);
+    println!("otc junior reserve token account: {}", otc_state.otc_junior_reserve_token_account);
+    println!("otc senior tranche token account: {}", otc_state.otc_senior_tranche_token_account);
+    println!("otc junior tranche token account: {}", otc_state.otc_junior_tranche_token_account);
+}
@@ -0,0 +1,169 @@
+mod commands;
+mod terms;
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use clap::{ ArgEnum, Parser, Subcommand };
+
+use crate::{ commands::Config, terms::Terms };
+
+#[derive(Parser)]
+#[clap(name = "vyper-otc-cli", version, about = "Vyper OTC command line interface")]
+struct Cli {
+    /// RPC url
+    #[clap(long, short = 'u', global = true, env = "VYPER_OTC_RPC_URL", default_value = "https://api.devnet.solana.com")]
+    url: String,
+
+    /// Signer keypair, pays the fees and the rent, not needed by `show` and `list`
+    #[clap(long, short = 'k', global = true, env = "VYPER_OTC_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug)]
+pub enum Side {
+    Senior,
+    Junior,
+}
+
+impl Side {
+    pub fn is_senior(self) -> bool {
+        matches!(self, Side::Senior)
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a vyper tranche configuration and the otc on top of it
+    Create {
+        /// TOML or JSON terms file, parsed as JSON if the extension is `.json`
+        #[clap(long)]
+        terms_file: Option<PathBuf>,
+
+        #[clap(flatten)]
+        terms: Terms,
+    },
+
+    /// Take a side of the otc, native SOL is wrapped from the signer lamports
+    Deposit {
+        otc_state: Pubkey,
+
+        #[clap(arg_enum)]
+        side: Side,
+
+        /// Reserve token account recorded as side beneficiary, its owner receives the position token, the signer associated token account if missing
+        #[clap(long)]
+        beneficiary: Option<Pubkey>,
+    },
+
+    /// Withdraw an unmatched side, burning its position token.
+    /// Native SOL refunds to the signer associated token account are unwrapped
+    Withdraw {
+        otc_state: Pubkey,
+
+        #[clap(arg_enum)]
+        side: Side,
+
+        /// Reserve token account receiving the refund, the signer associated token account if missing
+        #[clap(long)]
+        beneficiary: Option<Pubkey>,
+    },
+
+    /// Refresh the vyper fair value and redeem both sides
+    Settle {
+        otc_state: Pubkey,
+
+        /// Remaining accounts of the rate plugin refresh, e.g. the switchboard aggregators
+        #[clap(long = "rate-refresh-account")]
+        rate_refresh_accounts: Vec<Pubkey>,
+    },
+
+    /// Terminate a matched otc before settle start, signed by both position holders
+    EarlyTerminate {
+        otc_state: Pubkey,
+
+        /// Side held by the signer, the counterparty holds the other one
+        #[clap(arg_enum)]
+        side: Side,
+
+        /// Keypair of the other position holder
+        #[clap(long)]
+        counterparty_keypair: String,
+
+        /// Agreed share of the senior side in basis points, the current vyper fair value if missing
+        #[clap(long)]
+        senior_split_bps: Option<u16>,
+
+        /// Remaining accounts of the rate plugin refresh, needed without an agreed split
+        #[clap(long = "rate-refresh-account")]
+        rate_refresh_accounts: Vec<Pubkey>,
+    },
+
+    /// Redeem both sides in proportion to their deposits, once the settle deadline has passed
+    Unwind {
+        otc_state: Pubkey,
+    },
+
+    /// Claim a settled side, burning its position token.
+    /// Native SOL claims to the signer associated token account are unwrapped
+    Claim {
+        otc_state: Pubkey,
+
+        #[clap(arg_enum)]
+        side: Side,
+
+        /// Reserve token account receiving the claim, the signer associated token account if missing
+        #[clap(long)]
+        beneficiary: Option<Pubkey>,
+    },
+
+    /// Pay the unclaimed settled sides to their recorded beneficiaries, no beneficiary signature needed
+    Distribute {
+        otc_state: Pubkey,
+    },
+
+    /// Close the otc, the rent is refunded to the creator
+    Close {
+        otc_state: Pubkey,
+    },
+
+    /// Show an otc state
+    Show {
+        otc_state: Pubkey,
+    },
+
+    /// List all the otc states
+    List,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    // the signer keypair is only loaded by the commands sending transactions
+    let config = || Config::new(&cli.url, &cli.keypair);
+
+    match cli.command {
+        Command::Create { terms_file, terms } => {
+            let terms = match terms_file {
+                Some(path) => terms.merge(Terms::from_file(&path)?),
+                None => terms,
+            };
+            commands::create(&config()?, terms)
+        }
+        Command::Deposit { otc_state, side, beneficiary } => commands::deposit(&config()?, &otc_state, side, beneficiary),
+        Command::Withdraw { otc_state, side, beneficiary } => commands::withdraw(&config()?, &otc_state, side, beneficiary),
+        Command::Settle { otc_state, rate_refresh_accounts } => commands::settle(&config()?, &otc_state, &rate_refresh_accounts),
+        Command::EarlyTerminate { otc_state, side, counterparty_keypair, senior_split_bps, rate_refresh_accounts } => {
+            commands::early_terminate(&config()?, &otc_state, side, &counterparty_keypair, senior_split_bps, &rate_refresh_accounts)
+        }
+        Command::Unwind { otc_state } => commands::unwind(&config()?, &otc_state),
+        Command::Claim { otc_state, side, beneficiary } => commands::claim(&config()?, &otc_state, side, beneficiary),
+        Command::Distribute { otc_state } => commands::distribute(&config()?, &otc_state),
+        Command::Close { otc_state } => commands::close(&config()?, &otc_state),
+        Command::Show { otc_state } => commands::show(&commands::rpc_client(&cli.url), &otc_state),
+        Command::List => commands::list(&commands::rpc_client(&cli.url)),
+    }
+}
@@ -0,0 +1,127 @@
+use std::{ fs, path::Path };
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{ anyhow, Context, Result };
+use clap::Args;
+use serde::Deserialize;
+
+/// OTC terms, from flags or from a TOML/JSON terms file, flags take precedence
+#[derive(Args, Deserialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Terms {
+    /// Reserve mint deposited by both sides
+    #[clap(long)]
+    #[serde(default, with = "pubkey_opt")]
+    pub reserve_mint: Option<Pubkey>,
+
+    /// Senior side deposit amount, in reserve base units
+    #[clap(long)]
+    pub senior_deposit_amount: Option<u64>,
+
+    /// Junior side deposit amount, in reserve base units
+    #[clap(long)]
+    pub junior_deposit_amount: Option<u64>,
+
+    /// Deposit start unix timestamp, now if missing
+    #[clap(long)]
+    pub deposit_start: Option<i64>,
+
+    /// Deposit end unix timestamp
+    #[clap(long)]
+    pub deposit_end: Option<i64>,
+
+    /// Settle start unix timestamp
+    #[clap(long)]
+    pub settle_start: Option<i64>,
+
+    /// Unwind available after this unix timestamp if not settled, settle start plus the global grace period if missing
+    #[clap(long)]
+    pub settle_deadline: Option<i64>,
+
+    /// Only this wallet can take the senior side
+    #[clap(long)]
+    #[serde(default, with = "pubkey_opt")]
+    pub senior_counterparty: Option<Pubkey>,
+
+    /// Only this wallet can take the junior side
+    #[clap(long)]
+    #[serde(default, with = "pubkey_opt")]
+    pub junior_counterparty: Option<Pubkey>,
+
+    /// Lamports paid by the creator to the first settle caller, none if missing
+    #[clap(long)]
+    pub settle_bounty: Option<u64>,
+
+    /// Vyper rate plugin program
+    #[clap(long)]
+    #[serde(default, with = "pubkey_opt")]
+    pub rate_program: Option<Pubkey>,
+
+    /// Vyper rate plugin state, already initialized
+    #[clap(long)]
+    #[serde(default, with = "pubkey_opt")]
+    pub rate_program_state: Option<Pubkey>,
+
+    /// Vyper redeem logic plugin program
+    #[clap(long)]
+    #[serde(default, with = "pubkey_opt")]
+    pub redeem_logic_program: Option<Pubkey>,
+
+    /// Vyper redeem logic plugin state, already initialized
+    #[clap(long)]
+    #[serde(default, with = "pubkey_opt")]
+    pub redeem_logic_program_state: Option<Pubkey>,
+
+    /// Decimals of the vyper tranche mints
+    #[clap(long)]
+    pub tranche_mint_decimals: Option<u8>,
+}
+
+impl Terms {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).with_context(|| format!("reading terms file {}", path.display()))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&content).context("parsing json terms file"),
+            _ => toml::from_str(&content).context("parsing toml terms file"),
+        }
+    }
+
+    /// fills the missing flags with the terms file values
+    pub fn merge(self, other: Terms) -> Terms {
+        Terms {
+            reserve_mint: self.reserve_mint.or(other.reserve_mint),
+            senior_deposit_amount: self.senior_deposit_amount.or(other.senior_deposit_amount),
+            junior_deposit_amount: self.junior_deposit_amount.or(other.junior_deposit_amount),
+            deposit_start: self.deposit_start.or(other.deposit_start),
+            deposit_end: self.deposit_end.or(other.deposit_end),
+            settle_start: self.settle_start.or(other.settle_start),
+            settle_deadline: self.settle_deadline.or(other.settle_deadline),
+            senior_counterparty: self.senior_counterparty.or(other.senior_counterparty),
+            junior_counterparty: self.junior_counterparty.or(other.junior_counterparty),
+            settle_bounty: self.settle_bounty.or(other.settle_bounty),
+            rate_program: self.rate_program.or(other.rate_program),
+            rate_program_state: self.rate_program_state.or(other.rate_program_state),
+            redeem_logic_program: self.redeem_logic_program.or(other.redeem_logic_program),
+            redeem_logic_program_state: self.redeem_logic_program_state.or(other.redeem_logic_program_state),
+            tranche_mint_decimals: self.tranche_mint_decimals.or(other.tranche_mint_decimals),
+        }
+    }
+}
+
+pub fn required<T>(value: Option<T>, name: &str) -> Result<T> {
+    value.ok_or_else(|| anyhow!("missing `{}`, set it with a flag or in the terms file", name))
+}
+
+/// pubkeys are written as base58 strings in the terms file
+mod pubkey_opt {
+    use std::str::FromStr;
+
+    use anchor_lang::prelude::Pubkey;
+    use serde::{ de::Error, Deserialize, Deserializer };
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Pubkey>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| Pubkey::from_str(&value).map_err(D::Error::custom))
+            .transpose()
+    }
+}
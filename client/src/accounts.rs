@@ -0,0 +1,112 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use vyper_core::state::TrancheConfig;
+use vyper_otc::state::{ GlobalConfig, OtcState };
+
+use crate::{
+    errors::Result,
+    pda::{ find_global_config, find_vyper_reserve, find_vyper_tranche_authority },
+};
+
+pub fn decode_otc_state(data: &[u8]) -> Result<OtcState> {
+    Ok(OtcState::try_deserialize(&mut &data[..])?)
+}
+
+pub fn decode_global_config(data: &[u8]) -> Result<GlobalConfig> {
+    Ok(GlobalConfig::try_deserialize(&mut &data[..])?)
+}
+
+pub fn decode_tranche_config(data: &[u8]) -> Result<TrancheConfig> {
+    Ok(TrancheConfig::try_deserialize(&mut &data[..])?)
+}
+
+/// vyper-core accounts used by an otc
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VyperCoreAccounts {
+    pub vyper_core: Pubkey,
+    pub tranche_config: Pubkey,
+    pub tranche_authority: Pubkey,
+    pub reserve: Pubkey,
+    pub reserve_mint: Pubkey,
+    pub senior_tranche_mint: Pubkey,
+    pub junior_tranche_mint: Pubkey,
+    pub rate_program: Pubkey,
+    pub rate_program_state: Pubkey,
+    pub redeem_logic_program: Pubkey,
+    pub redeem_logic_program_state: Pubkey,
+}
+
+impl VyperCoreAccounts {
+    /// accounts of a tranche configuration not created yet
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        tranche_config: Pubkey,
+        reserve_mint: Pubkey,
+        senior_tranche_mint: Pubkey,
+        junior_tranche_mint: Pubkey,
+        rate_program: Pubkey,
+        rate_program_state: Pubkey,
+        redeem_logic_program: Pubkey,
+        redeem_logic_program_state: Pubkey,
+    ) -> Self {
+        Self {
+            vyper_core: vyper_core::ID,
+            tranche_config,
+            tranche_authority: find_vyper_tranche_authority(&tranche_config).0,
+            reserve: find_vyper_reserve(&tranche_config, &reserve_mint).0,
+            reserve_mint,
+            senior_tranche_mint,
+            junior_tranche_mint,
+            rate_program,
+            rate_program_state,
+            redeem_logic_program,
+            redeem_logic_program_state,
+        }
+    }
+
+    pub fn resolve(tranche_config_key: &Pubkey, tranche_config: &TrancheConfig) -> Self {
+        Self::new(
+            *tranche_config_key,
+            tranche_config.reserve_mint,
+            tranche_config.senior_tranche_mint,
+            tranche_config.junior_tranche_mint,
+            tranche_config.rate_program,
+            tranche_config.rate_program_state,
+            tranche_config.redeem_logic_program,
+            tranche_config.redeem_logic_program_state,
+        )
+    }
+}
+
+/// all the accounts needed to build the instructions of an initialized otc
+#[derive(Clone)]
+pub struct OtcAccounts {
+    pub otc_state_key: Pubkey,
+    pub otc_state: OtcState,
+    pub global_config: Pubkey,
+    pub vyper: VyperCoreAccounts,
+}
+
+impl OtcAccounts {
+    /// resolves the vyper-core accounts from the tranche configuration referenced by the otc state
+    pub fn resolve(otc_state_key: Pubkey, otc_state: OtcState, tranche_config: &TrancheConfig) -> Self {
+        let vyper = VyperCoreAccounts::resolve(&otc_state.vyper_tranche_config, tranche_config);
+        Self {
+            otc_state_key,
+            otc_state,
+            global_config: find_global_config().0,
+            vyper,
+        }
+    }
+
+    pub fn position_mint(&self, is_senior_side: bool) -> Pubkey {
+        if is_senior_side {
+            self.otc_state.senior_position_mint
+        } else {
+            self.otc_state.junior_position_mint
+        }
+    }
+
+    pub fn position_token_account(&self, is_senior_side: bool, owner: &Pubkey) -> Pubkey {
+        anchor_spl::associated_token::get_associated_token_address(owner, &self.position_mint(is_senior_side))
+    }
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum VyperOtcClientError {
+    #[error("account data could not be deserialized: {0}")]
+    AccountDidNotDeserialize(#[from] anchor_lang::error::Error),
+
+    #[cfg(feature = "rpc")]
+    #[error("rpc error: {0}")]
+    Rpc(#[from] solana_client::client_error::ClientError),
+}
+
+pub type Result<T> = std::result::Result<T, VyperOtcClientError>;
@@ -0,0 +1,511 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{ instruction::{ AccountMeta, Instruction }, system_program, sysvar },
+    InstructionData, ToAccountMetas,
+};
+use vyper_core::state::OwnerRestrictedIxFlags;
+use vyper_otc::instructions::{
+    ClaimInputData, DepositInputData, DistributeInputData, EarlyTerminateInputData, GlobalConfigInputData, InitializeInputData,
+    WithdrawInputData,
+};
+
+use crate::{
+    accounts::{ OtcAccounts, VyperCoreAccounts },
+    pda::{ find_global_config, find_junior_position_mint, find_otc_authority, find_senior_position_mint },
+};
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: vyper_otc::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// - - - - - - - - - - - -
+// Initialize
+
+/// vyper-core tranche configuration owned by `owner`, with deposits and redeems restricted to it,
+/// `vyper.tranche_config` and the tranche mints need to sign
+pub fn initialize_vyper_tranche_config(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    vyper: &VyperCoreAccounts,
+    tranche_mint_decimals: u8,
+) -> Instruction {
+    Instruction {
+        program_id: vyper_core::ID,
+        accounts: vyper_core::accounts::InitializeContext {
+            payer: *payer,
+            owner: *owner,
+            tranche_config: vyper.tranche_config,
+            tranche_authority: vyper.tranche_authority,
+            rate_program: vyper.rate_program,
+            rate_program_state: vyper.rate_program_state,
+            redeem_logic_program: vyper.redeem_logic_program,
+            redeem_logic_program_state: vyper.redeem_logic_program_state,
+            reserve_mint: vyper.reserve_mint,
+            reserve: vyper.reserve,
+            junior_tranche_mint: vyper.junior_tranche_mint,
+            senior_tranche_mint: vyper.senior_tranche_mint,
+            system_program: system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: vyper_core::instruction::Initialize {
+            input_data: vyper_core::instructions::InitializationData {
+                tranche_mint_decimals,
+                owner_restricted_ixs: (OwnerRestrictedIxFlags::DEPOSITS | OwnerRestrictedIxFlags::REDEEMS).bits(),
+                halt_flags: 0,
+            },
+        }
+        .data(),
+    }
+}
+
+/// accounts created by `initialize`, all of them but the position mints need to sign
+#[derive(Clone, Copy, Debug)]
+pub struct InitializeAccounts {
+    pub otc_state: Pubkey,
+    pub otc_senior_reserve_token_account: Pubkey,
+    pub otc_junior_reserve_token_account: Pubkey,
+    pub otc_senior_tranche_token_account: Pubkey,
+    pub otc_junior_tranche_token_account: Pubkey,
+}
+
+/// the vyper tranche configuration needs to be owned by the `otc_authority` of `accounts.otc_state`
+pub fn initialize(
+    signer: &Pubkey,
+    accounts: &InitializeAccounts,
+    vyper: &VyperCoreAccounts,
+    input_data: InitializeInputData,
+) -> Instruction {
+    build(
+        vyper_otc::accounts::InitializeContext {
+            otc_state: accounts.otc_state,
+            otc_authority: find_otc_authority(&accounts.otc_state).0,
+            global_config: find_global_config().0,
+            otc_senior_reserve_token_account: accounts.otc_senior_reserve_token_account,
+            otc_junior_reserve_token_account: accounts.otc_junior_reserve_token_account,
+            otc_senior_tranche_token_account: accounts.otc_senior_tranche_token_account,
+            otc_junior_tranche_token_account: accounts.otc_junior_tranche_token_account,
+            senior_position_mint: find_senior_position_mint(&accounts.otc_state).0,
+            junior_position_mint: find_junior_position_mint(&accounts.otc_state).0,
+            reserve_mint: vyper.reserve_mint,
+            senior_tranche_mint: vyper.senior_tranche_mint,
+            junior_tranche_mint: vyper.junior_tranche_mint,
+            vyper_tranche_config: vyper.tranche_config,
+            vyper_core: vyper.vyper_core,
+            rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+            token_program: anchor_spl::token::ID,
+            signer: *signer,
+        },
+        vyper_otc::instruction::Initialize { input_data },
+    )
+}
+
+// - - - - - - - - - - - -
+// Global Configuration
+
+/// signed by the program upgrade authority
+pub fn initialize_global_config(signer: &Pubkey, program_data: &Pubkey, input_data: GlobalConfigInputData) -> Instruction {
+    build(
+        vyper_otc::accounts::InitializeGlobalConfigContext {
+            global_config: find_global_config().0,
+            program: vyper_otc::ID,
+            program_data: *program_data,
+            system_program: system_program::ID,
+            signer: *signer,
+        },
+        vyper_otc::instruction::InitializeGlobalConfig { input_data },
+    )
+}
+
+pub fn update_global_config(admin: &Pubkey, input_data: GlobalConfigInputData) -> Instruction {
+    build(
+        vyper_otc::accounts::UpdateGlobalConfigContext {
+            global_config: find_global_config().0,
+            admin: *admin,
+        },
+        vyper_otc::instruction::UpdateGlobalConfig { input_data },
+    )
+}
+
+/// signed by the pending admin proposed with `update_global_config`
+pub fn accept_admin(new_admin: &Pubkey) -> Instruction {
+    build(
+        vyper_otc::accounts::AcceptAdminContext {
+            global_config: find_global_config().0,
+            new_admin: *new_admin,
+        },
+        vyper_otc::instruction::AcceptAdmin {},
+    )
+}
+
+pub fn set_paused(admin: &Pubkey, paused: bool) -> Instruction {
+    build(
+        vyper_otc::accounts::SetPausedContext {
+            global_config: find_global_config().0,
+            admin: *admin,
+        },
+        vyper_otc::instruction::SetPaused { paused },
+    )
+}
+
+impl OtcAccounts {
+
+    // - - - - - - - - - - - -
+    // Lifecycle
+
+    /// with `wrap_native` the lamports of `signer` are wrapped in `user_reserve_token_account` before the deposit,
+    /// `beneficiary_owner` owns `beneficiary_token_account` and receives the position token
+    pub fn deposit(
+        &self,
+        signer: &Pubkey,
+        user_reserve_token_account: &Pubkey,
+        beneficiary_token_account: &Pubkey,
+        beneficiary_owner: &Pubkey,
+        treasury_token_account: &Pubkey,
+        is_senior_side: bool,
+        wrap_native: bool,
+    ) -> Instruction {
+        build(
+            vyper_otc::accounts::DepositContext {
+                user_reserve_token_account: *user_reserve_token_account,
+                beneficiary_token_account: *beneficiary_token_account,
+                beneficiary_owner: *beneficiary_owner,
+                otc_state: self.otc_state_key,
+                otc_authority: self.otc_state.otc_authority,
+                global_config: self.global_config,
+                treasury_token_account: *treasury_token_account,
+                otc_senior_reserve_token_account: self.otc_state.otc_senior_reserve_token_account,
+                otc_junior_reserve_token_account: self.otc_state.otc_junior_reserve_token_account,
+                otc_senior_tranche_token_account: self.otc_state.otc_senior_tranche_token_account,
+                otc_junior_tranche_token_account: self.otc_state.otc_junior_tranche_token_account,
+                position_mint: self.position_mint(is_senior_side),
+                position_token_account: self.position_token_account(is_senior_side, beneficiary_owner),
+                reserve_mint: self.vyper.reserve_mint,
+                senior_tranche_mint: self.vyper.senior_tranche_mint,
+                junior_tranche_mint: self.vyper.junior_tranche_mint,
+                vyper_tranche_config: self.vyper.tranche_config,
+                vyper_tranche_authority: self.vyper.tranche_authority,
+                vyper_reserve: self.vyper.reserve,
+                vyper_core: self.vyper.vyper_core,
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                signer: *signer,
+            },
+            vyper_otc::instruction::Deposit {
+                input_data: DepositInputData { is_senior_side, wrap_native },
+            },
+        )
+    }
+
+    /// `signer` presents and burns the position token of the side,
+    /// with `unwrap_native` the beneficiary token account, owned by `signer`, is closed to `signer`
+    pub fn withdraw(
+        &self,
+        signer: &Pubkey,
+        user_reserve_token_account: &Pubkey,
+        beneficiary_token_account: &Pubkey,
+        is_senior_side: bool,
+        unwrap_native: bool,
+    ) -> Instruction {
+        build(
+            vyper_otc::accounts::WithdrawContext {
+                user_reserve_token_account: *user_reserve_token_account,
+                beneficiary_token_account: *beneficiary_token_account,
+                otc_state: self.otc_state_key,
+                otc_authority: self.otc_state.otc_authority,
+                otc_senior_reserve_token_account: self.otc_state.otc_senior_reserve_token_account,
+                otc_junior_reserve_token_account: self.otc_state.otc_junior_reserve_token_account,
+                position_mint: self.position_mint(is_senior_side),
+                position_token_account: self.position_token_account(is_senior_side, signer),
+                reserve_mint: self.vyper.reserve_mint,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token::ID,
+                signer: *signer,
+            },
+            vyper_otc::instruction::Withdraw {
+                input_data: WithdrawInputData { unwrap_native },
+            },
+        )
+    }
+
+    /// the rate plugin and `refresh_tranche_fair_value` need to be executed before in the same transaction
+    pub fn settle(&self, signer: &Pubkey) -> Instruction {
+        build(
+            vyper_otc::accounts::RedeemContext {
+                signer: *signer,
+                otc_state: self.otc_state_key,
+                otc_authority: self.otc_state.otc_authority,
+                global_config: self.global_config,
+                otc_senior_reserve_token_account: self.otc_state.otc_senior_reserve_token_account,
+                otc_junior_reserve_token_account: self.otc_state.otc_junior_reserve_token_account,
+                otc_senior_tranche_token_account: self.otc_state.otc_senior_tranche_token_account,
+                otc_junior_tranche_token_account: self.otc_state.otc_junior_tranche_token_account,
+                reserve_mint: self.vyper.reserve_mint,
+                senior_tranche_mint: self.vyper.senior_tranche_mint,
+                junior_tranche_mint: self.vyper.junior_tranche_mint,
+                vyper_tranche_config: self.vyper.tranche_config,
+                vyper_tranche_authority: self.vyper.tranche_authority,
+                vyper_reserve: self.vyper.reserve,
+                vyper_core: self.vyper.vyper_core,
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token::ID,
+            },
+            vyper_otc::instruction::Settle {},
+        )
+    }
+
+    /// fallback to `settle` once the settle deadline has passed, does not pay the settle bounty
+    pub fn unwind(&self, signer: &Pubkey) -> Instruction {
+        build(
+            vyper_otc::accounts::UnwindContext {
+                signer: *signer,
+                otc_state: self.otc_state_key,
+                otc_authority: self.otc_state.otc_authority,
+                otc_senior_reserve_token_account: self.otc_state.otc_senior_reserve_token_account,
+                otc_junior_reserve_token_account: self.otc_state.otc_junior_reserve_token_account,
+                otc_senior_tranche_token_account: self.otc_state.otc_senior_tranche_token_account,
+                otc_junior_tranche_token_account: self.otc_state.otc_junior_tranche_token_account,
+                reserve_mint: self.vyper.reserve_mint,
+                senior_tranche_mint: self.vyper.senior_tranche_mint,
+                junior_tranche_mint: self.vyper.junior_tranche_mint,
+                vyper_tranche_config: self.vyper.tranche_config,
+                vyper_tranche_authority: self.vyper.tranche_authority,
+                vyper_reserve: self.vyper.reserve,
+                vyper_core: self.vyper.vyper_core,
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token::ID,
+            },
+            vyper_otc::instruction::Unwind {},
+        )
+    }
+
+    /// both position holders need to sign, `senior_split_bps` is the agreed share of the senior side,
+    /// the current vyper fair value is used if missing and needs to be refreshed first
+    pub fn early_terminate(&self, senior_signer: &Pubkey, junior_signer: &Pubkey, senior_split_bps: Option<u16>) -> Instruction {
+        build(
+            vyper_otc::accounts::EarlyTerminateContext {
+                senior_signer: *senior_signer,
+                junior_signer: *junior_signer,
+                otc_state: self.otc_state_key,
+                otc_authority: self.otc_state.otc_authority,
+                global_config: self.global_config,
+                senior_position_mint: self.position_mint(true),
+                senior_position_token_account: self.position_token_account(true, senior_signer),
+                junior_position_mint: self.position_mint(false),
+                junior_position_token_account: self.position_token_account(false, junior_signer),
+                otc_senior_reserve_token_account: self.otc_state.otc_senior_reserve_token_account,
+                otc_junior_reserve_token_account: self.otc_state.otc_junior_reserve_token_account,
+                otc_senior_tranche_token_account: self.otc_state.otc_senior_tranche_token_account,
+                otc_junior_tranche_token_account: self.otc_state.otc_junior_tranche_token_account,
+                reserve_mint: self.vyper.reserve_mint,
+                senior_tranche_mint: self.vyper.senior_tranche_mint,
+                junior_tranche_mint: self.vyper.junior_tranche_mint,
+                vyper_tranche_config: self.vyper.tranche_config,
+                vyper_tranche_authority: self.vyper.tranche_authority,
+                vyper_reserve: self.vyper.reserve,
+                vyper_core: self.vyper.vyper_core,
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token::ID,
+            },
+            vyper_otc::instruction::EarlyTerminate {
+                input_data: EarlyTerminateInputData { senior_split_bps },
+            },
+        )
+    }
+
+    /// `signer` presents and burns the position token of the side,
+    /// with `unwrap_native` the beneficiary token account, owned by `signer`, is closed to `signer`
+    pub fn claim(
+        &self,
+        signer: &Pubkey,
+        beneficiary_token_account: &Pubkey,
+        is_senior_side: bool,
+        unwrap_native: bool,
+    ) -> Instruction {
+        build(
+            vyper_otc::accounts::ClaimContext {
+                otc_state: self.otc_state_key,
+                otc_authority: self.otc_state.otc_authority,
+                beneficiary_token_account: *beneficiary_token_account,
+                position_mint: self.position_mint(is_senior_side),
+                position_token_account: self.position_token_account(is_senior_side, signer),
+                otc_senior_reserve_token_account: self.otc_state.otc_senior_reserve_token_account,
+                otc_junior_reserve_token_account: self.otc_state.otc_junior_reserve_token_account,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token::ID,
+                signer: *signer,
+            },
+            vyper_otc::instruction::Claim {
+                input_data: ClaimInputData { unwrap_native },
+            },
+        )
+    }
+
+    /// pays the recorded beneficiary of a settled side, `beneficiary_owner` needs to hold the position token
+    pub fn distribute(&self, signer: &Pubkey, beneficiary_owner: &Pubkey, is_senior_side: bool) -> Option<Instruction> {
+        let beneficiary_token_account = if is_senior_side {
+            self.otc_state.senior_side_beneficiary
+        } else {
+            self.otc_state.junior_side_beneficiary
+        }?;
+        Some(build(
+            vyper_otc::accounts::DistributeContext {
+                otc_state: self.otc_state_key,
+                otc_authority: self.otc_state.otc_authority,
+                beneficiary_token_account,
+                position_mint: self.position_mint(is_senior_side),
+                position_token_account: self.position_token_account(is_senior_side, beneficiary_owner),
+                otc_senior_reserve_token_account: self.otc_state.otc_senior_reserve_token_account,
+                otc_junior_reserve_token_account: self.otc_state.otc_junior_reserve_token_account,
+                token_program: anchor_spl::token::ID,
+                signer: *signer,
+            },
+            vyper_otc::instruction::Distribute {
+                input_data: DistributeInputData { is_senior_side },
+            },
+        ))
+    }
+
+    /// reserve tokens left in the otc are swept to the creator associated token account
+    pub fn close(&self, signer: &Pubkey) -> Instruction {
+        build(
+            vyper_otc::accounts::CloseContext {
+                otc_state: self.otc_state_key,
+                otc_authority: self.otc_state.otc_authority,
+                creator: self.otc_state.creator,
+                creator_reserve_token_account: anchor_spl::associated_token::get_associated_token_address(
+                    &self.otc_state.creator,
+                    &self.vyper.reserve_mint,
+                ),
+                otc_senior_reserve_token_account: self.otc_state.otc_senior_reserve_token_account,
+                otc_junior_reserve_token_account: self.otc_state.otc_junior_reserve_token_account,
+                otc_senior_tranche_token_account: self.otc_state.otc_senior_tranche_token_account,
+                otc_junior_tranche_token_account: self.otc_state.otc_junior_tranche_token_account,
+                token_program: anchor_spl::token::ID,
+                signer: *signer,
+            },
+            vyper_otc::instruction::Close {},
+        )
+    }
+
+    /// signed by the creator, the taken side, if any, is refunded to its recorded beneficiary
+    /// and the reserve leftovers are swept to the creator associated token account,
+    /// `position_holder`, if any, co-signs and its position token of the taken side is burned
+    pub fn cancel(&self, position_holder: Option<&Pubkey>) -> Instruction {
+        let is_senior_side = self.otc_state.senior_side_beneficiary.is_some();
+        let beneficiary_token_account = self.otc_state.senior_side_beneficiary
+            .or(self.otc_state.junior_side_beneficiary)
+            .unwrap_or(self.otc_state.creator);
+        let (position_holder_key, position_token_account) = match position_holder {
+            Some(position_holder) => (*position_holder, self.position_token_account(is_senior_side, position_holder)),
+            None => (self.otc_state.otc_authority, self.otc_state.otc_authority),
+        };
+        let mut ix = build(
+            vyper_otc::accounts::CancelContext {
+                otc_state: self.otc_state_key,
+                otc_authority: self.otc_state.otc_authority,
+                beneficiary_token_account,
+                position_mint: self.position_mint(is_senior_side),
+                position_token_account,
+                position_holder: position_holder_key,
+                creator_reserve_token_account: anchor_spl::associated_token::get_associated_token_address(
+                    &self.otc_state.creator,
+                    &self.vyper.reserve_mint,
+                ),
+                otc_senior_reserve_token_account: self.otc_state.otc_senior_reserve_token_account,
+                otc_junior_reserve_token_account: self.otc_state.otc_junior_reserve_token_account,
+                otc_senior_tranche_token_account: self.otc_state.otc_senior_tranche_token_account,
+                otc_junior_tranche_token_account: self.otc_state.otc_junior_tranche_token_account,
+                token_program: anchor_spl::token::ID,
+                creator: self.otc_state.creator,
+            },
+            vyper_otc::instruction::Cancel {},
+        );
+        if position_holder.is_some() {
+            for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == position_holder_key) {
+                meta.is_signer = true;
+            }
+        }
+        ix
+    }
+
+    // - - - - - - - - - - - -
+    // Positions
+
+    /// signed by the position token holder, `beneficiary_token_account` is the one recorded for the side
+    pub fn transfer_position(
+        &self,
+        signer: &Pubkey,
+        beneficiary_token_account: &Pubkey,
+        new_owner: &Pubkey,
+        new_beneficiary_token_account: &Pubkey,
+        is_senior_side: bool,
+    ) -> Instruction {
+        build(
+            vyper_otc::accounts::TransferPositionContext {
+                otc_state: self.otc_state_key,
+                beneficiary_token_account: *beneficiary_token_account,
+                new_beneficiary_token_account: *new_beneficiary_token_account,
+                new_owner: *new_owner,
+                position_mint: self.position_mint(is_senior_side),
+                position_token_account: self.position_token_account(is_senior_side, signer),
+                new_position_token_account: self.position_token_account(is_senior_side, new_owner),
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                signer: *signer,
+            },
+            vyper_otc::instruction::TransferPosition {},
+        )
+    }
+
+    pub fn update_beneficiary(
+        &self,
+        signer: &Pubkey,
+        new_beneficiary_token_account: &Pubkey,
+        is_senior_side: bool,
+    ) -> Instruction {
+        build(
+            vyper_otc::accounts::UpdateBeneficiaryContext {
+                otc_state: self.otc_state_key,
+                new_beneficiary_token_account: *new_beneficiary_token_account,
+                otc_senior_reserve_token_account: self.otc_state.otc_senior_reserve_token_account,
+                position_mint: self.position_mint(is_senior_side),
+                position_token_account: self.position_token_account(is_senior_side, signer),
+                signer: *signer,
+            },
+            vyper_otc::instruction::UpdateBeneficiary {},
+        )
+    }
+
+    // - - - - - - - - - - - -
+    // Vyper Core
+
+    /// vyper-core fair value refresh, needs to follow the rate plugin refresh
+    pub fn refresh_tranche_fair_value(&self) -> Instruction {
+        Instruction {
+            program_id: vyper_core::ID,
+            accounts: vec![
+                AccountMeta::new(self.vyper.tranche_config, false),
+                AccountMeta::new(self.vyper.senior_tranche_mint, false),
+                AccountMeta::new(self.vyper.junior_tranche_mint, false),
+                AccountMeta::new_readonly(self.vyper.rate_program_state, false),
+                AccountMeta::new_readonly(self.vyper.redeem_logic_program, false),
+                AccountMeta::new_readonly(self.vyper.redeem_logic_program_state, false),
+            ],
+            data: vyper_core::instruction::RefreshTrancheFairValue {}.data(),
+        }
+    }
+}
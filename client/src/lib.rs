@@ -0,0 +1,20 @@
+//! Off-chain client for vyper-otc: instruction builders, PDA helpers,
+//! account decoding and resolution of the vyper-core accounts.
+
+pub mod accounts;
+pub mod errors;
+pub mod instructions;
+pub mod pda;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+
+pub use accounts::*;
+pub use errors::*;
+pub use instructions::*;
+pub use pda::*;
+
+pub use vyper_otc::{
+    instructions::{ GlobalConfigInputData, InitializeInputData },
+    state::{ GlobalConfig, OtcState, OtcStatus },
+    ID as VYPER_OTC_PROGRAM_ID,
+};
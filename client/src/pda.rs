@@ -0,0 +1,29 @@
+use anchor_lang::prelude::Pubkey;
+use vyper_otc::state::GlobalConfig;
+
+/// `otc_authority` PDA, owner of the otc token accounts and of the vyper tranche configuration
+pub fn find_otc_authority(otc_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[otc_state.as_ref(), b"authority"], &vyper_otc::ID)
+}
+
+pub fn find_global_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GlobalConfig::SEED], &vyper_otc::ID)
+}
+
+pub fn find_senior_position_mint(otc_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[otc_state.as_ref(), b"senior_position"], &vyper_otc::ID)
+}
+
+pub fn find_junior_position_mint(otc_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[otc_state.as_ref(), b"junior_position"], &vyper_otc::ID)
+}
+
+/// vyper-core tranche configuration authority
+pub fn find_vyper_tranche_authority(tranche_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[tranche_config.as_ref(), b"authority"], &vyper_core::ID)
+}
+
+/// vyper-core reserve token account of a tranche configuration
+pub fn find_vyper_reserve(tranche_config: &Pubkey, reserve_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[tranche_config.as_ref(), reserve_mint.as_ref()], &vyper_core::ID)
+}
@@ -0,0 +1,52 @@
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{ RpcAccountInfoConfig, RpcProgramAccountsConfig },
+    rpc_filter::{ Memcmp, MemcmpEncodedBytes, RpcFilterType },
+};
+use vyper_otc::state::{ GlobalConfig, OtcState };
+
+use crate::{
+    accounts::{ decode_global_config, decode_otc_state, decode_tranche_config, OtcAccounts },
+    errors::Result,
+    pda::find_global_config,
+};
+
+pub fn fetch_otc_state(rpc: &RpcClient, otc_state: &Pubkey) -> Result<OtcState> {
+    decode_otc_state(&rpc.get_account_data(otc_state)?)
+}
+
+pub fn fetch_global_config(rpc: &RpcClient) -> Result<GlobalConfig> {
+    decode_global_config(&rpc.get_account_data(&find_global_config().0)?)
+}
+
+/// fetches the otc state and its vyper tranche configuration
+pub fn fetch_otc_accounts(rpc: &RpcClient, otc_state_key: &Pubkey) -> Result<OtcAccounts> {
+    let otc_state = fetch_otc_state(rpc, otc_state_key)?;
+    let tranche_config = decode_tranche_config(&rpc.get_account_data(&otc_state.vyper_tranche_config)?)?;
+    Ok(OtcAccounts::resolve(*otc_state_key, otc_state, &tranche_config))
+}
+
+/// all the otc states owned by the program, filtered by discriminator
+pub fn fetch_all_otc_states(rpc: &RpcClient) -> Result<Vec<(Pubkey, OtcState)>> {
+    let accounts = rpc.get_program_accounts_with_config(
+        &vyper_otc::ID,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp {
+                offset: 0,
+                bytes: MemcmpEncodedBytes::Bytes(OtcState::discriminator().to_vec()),
+                encoding: None,
+            })]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            with_context: None,
+        },
+    )?;
+    accounts
+        .into_iter()
+        .map(|(key, account)| Ok((key, decode_otc_state(&account.data)?)))
+        .collect()
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use rust_decimal::{ prelude::FromPrimitive, Decimal };
+
+declare_id!("9TmNGquis5NMjt68j2XgL8qJCiueuH6coEmfHMUf9NUM");
+
+/// Vyper rate plugin returning the fair value set by its authority.
+/// Only meant for tests, it lets them pick the rate used by `settle`,
+/// so it's kept out of `anchor build` and only loaded by the test validator.
+#[program]
+pub mod rate_mock {
+    use super::*;
+
+    pub fn initialize(ctx: Context<InitializeContext>) -> Result<()> {
+        let rate_data = &mut ctx.accounts.rate_data;
+        rate_data.fair_value = [[0; 16]; 10];
+        rate_data.refreshed_slot = Clock::get()?.slot;
+        rate_data.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
+
+    pub fn set_fair_value(ctx: Context<SetFairValueContext>, fair_value: f64) -> Result<()> {
+        let rate_data = &mut ctx.accounts.rate_data;
+        rate_data.fair_value[0] = Decimal::from_f64(fair_value)
+            .ok_or(RateMockErrorCode::MathError)?
+            .serialize();
+        rate_data.refreshed_slot = Clock::get()?.slot;
+        msg!("fair value: {:?}", rate_data.fair_value[0]);
+        Ok(())
+    }
+
+    /// same interface as the other rate plugins, only updates the refreshed slot
+    pub fn refresh(ctx: Context<RefreshContext>) -> Result<()> {
+        ctx.accounts.rate_data.refreshed_slot = Clock::get()?.slot;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeContext<'info> {
+
+    /// Rate state initialized
+    #[account(init, payer = signer, space = RateState::LEN)]
+    pub rate_data: Box<Account<'info, RateState>>,
+
+    /// CHECK: Authority allowed to set the fair value
+    pub authority: AccountInfo<'info>,
+
+    /// Signer account
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFairValueContext<'info> {
+
+    /// Rate state
+    #[account(mut, has_one = authority)]
+    pub rate_data: Box<Account<'info, RateState>>,
+
+    /// Rate state authority
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshContext<'info> {
+
+    /// Rate state
+    #[account(mut)]
+    pub rate_data: Box<Account<'info, RateState>>,
+}
+
+/// layout read by vyper-core, `fair_value` and `refreshed_slot` need to come first
+#[account]
+pub struct RateState {
+    pub fair_value: [[u8; 16]; 10],
+    pub refreshed_slot: u64,
+    pub authority: Pubkey,
+}
+
+impl RateState {
+    pub const LEN: usize = 8 + // discriminator
+    16*10 + // pub fair_value: [[u8; 16]; 10],
+    8 + // pub refreshed_slot: u64,
+    32 // pub authority: Pubkey,
+    ;
+}
+
+#[error_code]
+pub enum RateMockErrorCode {
+    #[msg("math error")]
+    MathError,
+}
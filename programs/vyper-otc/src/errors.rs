@@ -16,7 +16,7 @@ pub enum VyperOtcErrorCode {
 
     #[msg("deposit is closed")]
     DepositClosed,
-    
+
     #[msg("both positions taken")]
     BothPositionsTaken,
 
@@ -30,5 +30,56 @@ pub enum VyperOtcErrorCode {
     SettleNotExecutedYet,
     
     #[msg("settle already executed")]
-    SettleAlreadyExecuted
+    SettleAlreadyExecuted,
+
+    #[msg("invalid otc status")]
+    InvalidOtcStatus,
+
+    #[msg("side already withdrawn")]
+    SideAlreadyWithdrawn,
+
+    #[msg("side already claimed")]
+    SideAlreadyClaimed,
+
+    #[msg("math error")]
+    MathError,
+
+    #[msg("invalid global config")]
+    InvalidGlobalConfig,
+
+    #[msg("otc duration not allowed")]
+    DurationNotAllowed,
+
+    #[msg("vyper plugin program not allowed")]
+    PluginProgramNotAllowed,
+
+    #[msg("program is paused")]
+    ProgramPaused,
+
+    #[msg("position mint not found")]
+    PositionMintNotFound,
+
+    #[msg("signer does not hold the position token")]
+    PositionTokenNotHeld,
+
+    #[msg("reserve mint is not the native mint")]
+    NativeMintRequired,
+
+    #[msg("settle deadline not reached")]
+    SettleDeadlineNotReached,
+
+    #[msg("early termination is only available before settle start")]
+    EarlyTerminationNotAvailable,
+
+    #[msg("invalid split")]
+    InvalidSplit,
+
+    #[msg("deposit is not open yet")]
+    DepositNotOpenYet,
+
+    #[msg("signer is not the designated counterparty")]
+    CounterpartyNotAllowed,
+
+    #[msg("signer is not the pending admin")]
+    NotPendingAdmin
 }
@@ -0,0 +1,45 @@
+use crate::{
+    errors::{ VyperOtcErrorCode },
+    state::{ GlobalConfig }
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAdminContext<'info> {
+
+    /// Global Configuration
+    #[account(mut, seeds = [GlobalConfig::SEED], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Pending admin proposed by the current one
+    pub new_admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<AcceptAdminContext>) -> Result<()> {
+
+    // the admin is rotated only once the proposed one proves it controls its key
+    require!(
+        ctx.accounts.global_config.pending_admin == Some(ctx.accounts.new_admin.key()),
+        VyperOtcErrorCode::NotPendingAdmin
+    );
+
+    let global_config = &mut ctx.accounts.global_config;
+    let old_admin = global_config.admin;
+    global_config.admin = ctx.accounts.new_admin.key();
+    global_config.pending_admin = None;
+
+    emit!(AdminAcceptEvent {
+        global_config: ctx.accounts.global_config.key(),
+        old_admin,
+        new_admin: ctx.accounts.new_admin.key(),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct AdminAcceptEvent {
+    pub global_config: Pubkey,
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
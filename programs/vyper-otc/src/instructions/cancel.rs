@@ -0,0 +1,191 @@
+use crate::{
+    errors::{ VyperOtcErrorCode },
+    instructions::close::sweep_reserve_leftovers,
+    state::{ OtcState, OtcStatus }
+};
+use anchor_lang::{prelude::*, AccountsClose};
+use anchor_spl::token::{self, Burn, TokenAccount, Token, CloseAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct CancelContext<'info> {
+
+    /// otc_state configuration
+    #[account(
+        mut,
+        has_one = otc_senior_reserve_token_account,
+        has_one = otc_junior_reserve_token_account,
+        has_one = otc_senior_tranche_token_account,
+        has_one = otc_junior_tranche_token_account,
+        has_one = otc_authority,
+        has_one = creator)]
+    pub otc_state: Box<Account<'info, OtcState>>,
+
+    /// CHECK: Vault Configuration Authority
+    #[account(seeds = [otc_state.key().as_ref(), b"authority".as_ref()], bump)]
+    pub otc_authority: AccountInfo<'info>,
+
+    /// CHECK: beneficiary token account of the taken side, ignored if no side has been taken
+    #[account(mut)]
+    pub beneficiary_token_account: AccountInfo<'info>,
+
+    /// CHECK: position mint of the taken side, only used to burn the position token
+    #[account(mut)]
+    pub position_mint: AccountInfo<'info>,
+
+    /// CHECK: position token account of the taken side, the position token is burned when its holder signs
+    #[account(mut)]
+    pub position_token_account: AccountInfo<'info>,
+
+    /// CHECK: position token holder, the creator cancel doesn't depend on it and it only signs to burn the position token
+    #[account()]
+    pub position_holder: AccountInfo<'info>,
+
+    /// CHECK: creator reserve token account, receives the reserve tokens left in the otc,
+    /// only checked when a non-native reserve token account is not empty
+    #[account(mut)]
+    pub creator_reserve_token_account: AccountInfo<'info>,
+
+    // - - - - - - - - - - - -
+    // OTC Token Accounts
+
+    /// OTC senior reserve token account
+    #[account(mut, token::authority = otc_authority)]
+    pub otc_senior_reserve_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// OTC junior reserve token account
+    #[account(mut, token::authority = otc_authority)]
+    pub otc_junior_reserve_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// OTC senior tranche token account
+    #[account(mut, token::authority = otc_authority)]
+    pub otc_senior_tranche_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// OTC junior tranche token account
+    #[account(mut, token::authority = otc_authority)]
+    pub otc_junior_tranche_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// OTC creator, receives the rent of the closed accounts
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelContext>) -> Result<()> {
+
+    // cancel is not possible if we have both sides taken
+    if ctx.accounts.otc_state.both_sides_taken() {
+        return err!(VyperOtcErrorCode::BothPositionsTaken);
+    }
+    require!(ctx.accounts.otc_state.status == OtcStatus::Open, VyperOtcErrorCode::InvalidOtcStatus);
+
+    // refund the deposit of the taken side, if any, to the recorded beneficiary,
+    // the position token is worthless once the otc state is closed and is burned if its holder signs
+    let mut refund: Option<(bool, u64)> = None;
+    for (is_senior, side_beneficiary, position_mint, deposit_amount, reserve_token_account) in [
+        (
+            true,
+            ctx.accounts.otc_state.senior_side_beneficiary,
+            ctx.accounts.otc_state.senior_position_mint,
+            ctx.accounts.otc_state.senior_deposit_amount,
+            &ctx.accounts.otc_senior_reserve_token_account,
+        ),
+        (
+            false,
+            ctx.accounts.otc_state.junior_side_beneficiary,
+            ctx.accounts.otc_state.junior_position_mint,
+            ctx.accounts.otc_state.junior_deposit_amount,
+            &ctx.accounts.otc_junior_reserve_token_account,
+        ),
+    ] {
+        if let Some(side_beneficiary) = side_beneficiary {
+            require_keys_eq!(side_beneficiary, ctx.accounts.beneficiary_token_account.key(), VyperOtcErrorCode::BeneficiaryNotFound);
+
+            msg!("refund {} side", if is_senior { "senior" } else { "junior" });
+            token::transfer(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: reserve_token_account.to_account_info(),
+                    to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                    authority: ctx.accounts.otc_authority.to_account_info(),
+                },
+                &[&ctx.accounts.otc_state.authority_seeds()]
+            ), deposit_amount)?;
+
+            if ctx.accounts.position_holder.is_signer {
+                let position_token_account = Account::<TokenAccount>::try_from(&ctx.accounts.position_token_account)?;
+                require_keys_eq!(position_token_account.mint, position_mint, VyperOtcErrorCode::PositionMintNotFound);
+                require_keys_eq!(position_token_account.owner, ctx.accounts.position_holder.key(), VyperOtcErrorCode::PositionTokenNotHeld);
+                require_gte!(position_token_account.amount, 1, VyperOtcErrorCode::PositionTokenNotHeld);
+
+                msg!("burn position token");
+                token::burn(CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.position_mint.to_account_info(),
+                        to: ctx.accounts.position_token_account.to_account_info(),
+                        authority: ctx.accounts.position_holder.to_account_info(),
+                    },
+                ), 1)?;
+            }
+
+            refund = Some((is_senior, deposit_amount));
+        }
+    }
+
+    // tokens sent to the reserve accounts on top of the deposit are swept to the creator
+    // -> Non-native account can only be closed if its balance is zero
+    ctx.accounts.otc_senior_reserve_token_account.reload()?;
+    ctx.accounts.otc_junior_reserve_token_account.reload()?;
+    sweep_reserve_leftovers(
+        ctx.accounts.token_program.to_account_info(),
+        [&ctx.accounts.otc_senior_reserve_token_account, &ctx.accounts.otc_junior_reserve_token_account],
+        ctx.accounts.creator_reserve_token_account.to_account_info(),
+        &ctx.accounts.creator.key(),
+        ctx.accounts.otc_authority.to_account_info(),
+        &ctx.accounts.otc_state.authority_seeds(),
+    )?;
+
+    msg!("close token accounts");
+    for account_to_close in [
+        ctx.accounts.otc_senior_reserve_token_account.to_account_info(),
+        ctx.accounts.otc_junior_reserve_token_account.to_account_info(),
+        ctx.accounts.otc_senior_tranche_token_account.to_account_info(),
+        ctx.accounts.otc_junior_tranche_token_account.to_account_info()
+    ] {
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: account_to_close,
+                authority: ctx.accounts.otc_authority.to_account_info(),
+                destination: ctx.accounts.creator.to_account_info()
+            },
+            &[&ctx.accounts.otc_state.authority_seeds()]
+        ))?;
+    }
+
+    // an unpaid settle bounty is refunded to the creator with the otc state rent
+    msg!("close otc state");
+    ctx.accounts.otc_state.status = OtcStatus::Closed;
+    ctx.accounts.otc_state.close(ctx.accounts.creator.to_account_info())?;
+
+    emit!(ContractCancelled {
+        otc_state: ctx.accounts.otc_state.key(),
+        creator: ctx.accounts.creator.key(),
+        is_senior_side: refund.map(|(is_senior, _)| is_senior),
+        beneficiary: refund.map(|_| ctx.accounts.beneficiary_token_account.key()),
+        amount: refund.map(|(_, amount)| amount).unwrap_or(0),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ContractCancelled {
+    pub otc_state: Pubkey,
+    pub creator: Pubkey,
+    pub is_senior_side: Option<bool>,
+    pub beneficiary: Option<Pubkey>,
+    pub amount: u64,
+}
@@ -1,25 +1,36 @@
 use crate::{
     errors::{ VyperOtcErrorCode },
-    state::{ OtcState }
+    state::{ OtcState, OtcStatus }
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, self, Transfer};
+use anchor_spl::token::{Burn, CloseAccount, Mint, Token, TokenAccount, self, Transfer};
 
 #[derive(Accounts)]
 pub struct ClaimContext<'info> {
 
     /// Vault Configuration initialized
-    #[account(has_one = otc_senior_reserve_token_account, has_one = otc_junior_reserve_token_account)]
+    #[account(mut, has_one = otc_senior_reserve_token_account, has_one = otc_junior_reserve_token_account)]
     pub otc_state: Box<Account<'info, OtcState>>,
 
     /// CHECK: Vault Configuration Authority
     #[account(seeds = [otc_state.key().as_ref(), b"authority".as_ref()], bump)]
     pub otc_authority: AccountInfo<'info>,
 
-    /// User reserve token account
-    #[account(mut, token::authority = signer)]
+    /// Token account receiving the redeemed assets
+    #[account(mut, constraint = beneficiary_token_account.mint == otc_senior_reserve_token_account.mint)]
     pub beneficiary_token_account: Box<Account<'info, TokenAccount>>,
 
+    // - - - - - - - - - - - - 
+    // Position Token
+
+    /// Position mint of the side to claim
+    #[account(mut)]
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    /// Signer position token account, the position token is burned
+    #[account(mut, token::mint = position_mint, token::authority = signer)]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
     // - - - - - - - - - - - - 
     // OTC Token Accounts
 
@@ -46,54 +57,112 @@ impl<'info> ClaimContext<'info> {
     
 }
 
-pub fn handler(ctx: Context<ClaimContext>) -> Result<()> {
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug)]
+pub struct ClaimInputData {
+    /// close the beneficiary token account to the signer, the reserve mint needs to be the native mint
+    pub unwrap_native: bool,
+}
+
+pub fn handler(ctx: Context<ClaimContext>, input_data: ClaimInputData) -> Result<()> {
     
     // check that assets can be redeemed
-    require!(ctx.accounts.otc_state.settle_executed, VyperOtcErrorCode::SettleNotExecutedYet);
-
-    // check beneficiary provided
-
-    let mut is_senior_opt: Option<bool> = None;
-
-    if let Some(senior_side_beneficiary) = ctx.accounts.otc_state.senior_side_beneficiary {
-        if senior_side_beneficiary == ctx.accounts.beneficiary_token_account.key() {
-            is_senior_opt = Some(true);
-        }
-    } 
-
-    if let Some(junior_side_beneficiary) = ctx.accounts.otc_state.junior_side_beneficiary {
-        if junior_side_beneficiary == ctx.accounts.beneficiary_token_account.key() {
-            is_senior_opt = Some(false);
-        }
+    if let OtcStatus::Open | OtcStatus::Matched = ctx.accounts.otc_state.status {
+        return err!(VyperOtcErrorCode::SettleNotExecutedYet);
+    }
+    require!(
+        ctx.accounts.otc_state.status == OtcStatus::Settled || ctx.accounts.otc_state.status == OtcStatus::Claimed,
+        VyperOtcErrorCode::InvalidOtcStatus
+    );
+
+    // the side is selected by the position mint presented
+    let is_senior = ctx.accounts.otc_state
+        .position_side(&ctx.accounts.position_mint.key())
+        .ok_or(VyperOtcErrorCode::PositionMintNotFound)?;
+    let claimed_amount = if is_senior {
+        ctx.accounts.otc_state.senior_claimed_amount
+    } else {
+        ctx.accounts.otc_state.junior_claimed_amount
+    };
+    if claimed_amount.is_some() {
+        return err!(VyperOtcErrorCode::SideAlreadyClaimed);
+    }
+    require_gte!(ctx.accounts.position_token_account.amount, 1, VyperOtcErrorCode::PositionTokenNotHeld);
+
+    // burn the position token
+    token::burn(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.position_mint.to_account_info(),
+            to: ctx.accounts.position_token_account.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+        },
+    ), 1)?;
+
+    // only the amount redeemed for the side is paid out
+    let (amount, source_account) = if is_senior {
+        (ctx.accounts.otc_state.senior_redeemed_amount, &ctx.accounts.otc_senior_reserve_token_account)
+    } else {
+        (ctx.accounts.otc_state.junior_redeemed_amount, &ctx.accounts.otc_junior_reserve_token_account)
+    };
+
+    // transfer assets
+    token::transfer(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: source_account.to_account_info(),
+            to: ctx.accounts.beneficiary_token_account.to_account_info(),
+            authority: ctx.accounts.otc_authority.to_account_info(),
+        },
+        &[&ctx.accounts.otc_state.authority_seeds()]
+    ), amount)?;
+
+    // unwrap to the signer, who needs to own the beneficiary token account
+    if input_data.unwrap_native {
+        require_keys_eq!(ctx.accounts.otc_senior_reserve_token_account.mint, token::spl_token::native_mint::ID, VyperOtcErrorCode::NativeMintRequired);
+        token::close_account(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.beneficiary_token_account.to_account_info(),
+                destination: ctx.accounts.signer.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ))?;
     }
 
-    if let Some(is_senior) = is_senior_opt {
-        // transfer assets
-        if is_senior {
-            token::transfer(CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.otc_senior_reserve_token_account.to_account_info(),
-                    to: ctx.accounts.beneficiary_token_account.to_account_info(),
-                    authority: ctx.accounts.otc_authority.to_account_info(),
-                },
-                &[&ctx.accounts.otc_state.authority_seeds()]
-            ), ctx.accounts.otc_senior_reserve_token_account.amount)?;
-        } else {
-            token::transfer(CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.otc_junior_reserve_token_account.to_account_info(),
-                    to: ctx.accounts.beneficiary_token_account.to_account_info(),
-                    authority: ctx.accounts.otc_authority.to_account_info(),
-                },
-                &[&ctx.accounts.otc_state.authority_seeds()]
-            ), ctx.accounts.otc_junior_reserve_token_account.amount)?;
-        }
+    // save claimed amount
+    let otc_state = &mut ctx.accounts.otc_state;
+    if is_senior {
+        otc_state.senior_claimed_amount = Some(amount);
+    } else {
+        otc_state.junior_claimed_amount = Some(amount);
     }
-    else {
-        return err!(VyperOtcErrorCode::BeneficiaryNotFound);
+    if otc_state.senior_claimed_amount.is_some() && otc_state.junior_claimed_amount.is_some() {
+        otc_state.status = OtcStatus::Claimed;
     }
 
+    ctx.accounts.otc_senior_reserve_token_account.reload()?;
+    ctx.accounts.otc_junior_reserve_token_account.reload()?;
+
+    emit!(ClaimEvent {
+        otc_state: ctx.accounts.otc_state.key(),
+        is_senior_side: is_senior,
+        beneficiary: ctx.accounts.beneficiary_token_account.key(),
+        position_token_account: ctx.accounts.position_token_account.key(),
+        amount,
+        senior_reserve_amount: ctx.accounts.otc_senior_reserve_token_account.amount,
+        junior_reserve_amount: ctx.accounts.otc_junior_reserve_token_account.amount,
+    });
+
     Ok(())
 }
+
+#[event]
+pub struct ClaimEvent {
+    pub otc_state: Pubkey,
+    pub is_senior_side: bool,
+    pub beneficiary: Pubkey,
+    pub position_token_account: Pubkey,
+    pub amount: u64,
+    pub senior_reserve_amount: u64,
+    pub junior_reserve_amount: u64,
+}
@@ -1,9 +1,9 @@
 use crate::{
     errors::{ VyperOtcErrorCode },
-    state::{ OtcState }
+    state::{ OtcState, OtcStatus }
 };
 use anchor_lang::{prelude::*, AccountsClose};
-use anchor_spl::token::{self, TokenAccount, Token, CloseAccount};
+use anchor_spl::token::{self, TokenAccount, Token, CloseAccount, Transfer};
 #[derive(Accounts)]
 pub struct CloseContext<'info> {
 
@@ -14,13 +14,23 @@ pub struct CloseContext<'info> {
         has_one = otc_junior_reserve_token_account,
         has_one = otc_senior_tranche_token_account,
         has_one = otc_junior_tranche_token_account,
-        has_one = otc_authority,)]
+        has_one = otc_authority,
+        has_one = creator)]
     pub otc_state: Box<Account<'info, OtcState>>,
 
     /// CHECK: Vault Configuration Authority
     #[account(seeds = [otc_state.key().as_ref(), b"authority".as_ref()], bump)]
     pub otc_authority: AccountInfo<'info>,
 
+    /// CHECK: OTC creator, receives the rent of the closed accounts
+    #[account(mut)]
+    pub creator: AccountInfo<'info>,
+
+    /// CHECK: creator reserve token account, receives the reserve tokens left in the otc,
+    /// only checked when a non-native reserve token account is not empty
+    #[account(mut)]
+    pub creator_reserve_token_account: AccountInfo<'info>,
+
     // - - - - - - - - - - - - 
     // OTC Token Accounts
 
@@ -52,11 +62,31 @@ pub fn handler(ctx: Context<CloseContext>) -> Result<()> {
     
     // check if deposit is closed
     let clock = Clock::get()?;
-    require_gt!(clock.unix_timestamp, ctx.accounts.otc_state.deposit_end, VyperOtcErrorCode::DepositOpen);
+    require!(ctx.accounts.otc_state.is_deposit_expired(clock.unix_timestamp), VyperOtcErrorCode::DepositOpen);
+
+    // check that no funds are left in the otc
+    match ctx.accounts.otc_state.status {
+        OtcStatus::Open => require!(
+            ctx.accounts.otc_state.senior_side_beneficiary.is_none() && ctx.accounts.otc_state.junior_side_beneficiary.is_none(),
+            VyperOtcErrorCode::InvalidOtcStatus
+        ),
+        OtcStatus::Refunded | OtcStatus::Claimed => {},
+        _ => return err!(VyperOtcErrorCode::InvalidOtcStatus),
+    }
 
-    // NB we don't have to check if token accounts have zero balance
-    // this is already checked by the token program below
+    // payouts are exact amounts, rounding residues and tokens sent to the reserve accounts are swept to the creator
     // -> Non-native account can only be closed if its balance is zero
+    sweep_reserve_leftovers(
+        ctx.accounts.token_program.to_account_info(),
+        [&ctx.accounts.otc_senior_reserve_token_account, &ctx.accounts.otc_junior_reserve_token_account],
+        ctx.accounts.creator_reserve_token_account.to_account_info(),
+        &ctx.accounts.creator.key(),
+        ctx.accounts.otc_authority.to_account_info(),
+        &ctx.accounts.otc_state.authority_seeds(),
+    )?;
+
+    // NB tranche token accounts are empty once redeemed or never deposited,
+    // this is checked by the token program below
 
     msg!("close token accounts");
     for account_to_close in [
@@ -70,25 +100,59 @@ pub fn handler(ctx: Context<CloseContext>) -> Result<()> {
             CloseAccount {
                 account: account_to_close,
                 authority: ctx.accounts.otc_authority.to_account_info(),
-                destination: ctx.accounts.signer.to_account_info()
+                destination: ctx.accounts.creator.to_account_info()
             },
             &[&ctx.accounts.otc_state.authority_seeds()]
         ))?;
     }
 
+    // an unpaid settle bounty is refunded to the creator with the otc state rent
     msg!("close otc state");
-    ctx.accounts.otc_state.close(ctx.accounts.signer.to_account_info())?;
+    ctx.accounts.otc_state.status = OtcStatus::Closed;
+    ctx.accounts.otc_state.close(ctx.accounts.creator.to_account_info())?;
 
     emit!(ContractClosed {
         otc_state: ctx.accounts.otc_state.key(),
+        creator: ctx.accounts.creator.key(),
         signer: ctx.accounts.signer.key(),
     });
 
     Ok(())
 }
 
+/// transfers the balance left in the non-native reserve accounts to the creator reserve token account,
+/// which is only checked when there is something to sweep
+pub fn sweep_reserve_leftovers<'info>(
+    token_program: AccountInfo<'info>,
+    reserve_token_accounts: [&Account<'info, TokenAccount>; 2],
+    creator_reserve_token_account: AccountInfo<'info>,
+    creator: &Pubkey,
+    authority: AccountInfo<'info>,
+    authority_seeds: &[&[u8]],
+) -> Result<()> {
+    for reserve_token_account in reserve_token_accounts {
+        if reserve_token_account.amount == 0 || reserve_token_account.is_native() {
+            continue;
+        }
+        let creator_reserve = Account::<TokenAccount>::try_from(&creator_reserve_token_account)?;
+        require_keys_eq!(creator_reserve.mint, reserve_token_account.mint, ErrorCode::ConstraintTokenMint);
+        require_keys_eq!(creator_reserve.owner, *creator, ErrorCode::ConstraintTokenOwner);
+        token::transfer(CpiContext::new_with_signer(
+            token_program.clone(),
+            Transfer {
+                from: reserve_token_account.to_account_info(),
+                to: creator_reserve_token_account.clone(),
+                authority: authority.clone(),
+            },
+            &[authority_seeds]
+        ), reserve_token_account.amount)?;
+    }
+    Ok(())
+}
+
 #[event]
 pub struct ContractClosed {
     pub otc_state: Pubkey,
+    pub creator: Pubkey,
     pub signer: Pubkey
 }
\ No newline at end of file
@@ -1,22 +1,26 @@
 use crate::{
     errors::{ VyperOtcErrorCode },
-    state::{ OtcState }
+    state::{ GlobalConfig, OtcState, OtcStatus }
 };
-use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount, Transfer, self};
+use anchor_lang::{prelude::*, solana_program::{program::invoke, system_instruction}};
+use anchor_spl::{associated_token::AssociatedToken, token::{Mint, MintTo, SyncNative, Token, TokenAccount, Transfer, self}};
 use vyper_core::{state::{TrancheConfig}, program::VyperCore};
 
 #[derive(Accounts)]
 pub struct DepositContext<'info> {
 
-    /// User reserve token account
-    #[account(mut, token::authority = signer)]
+    /// User reserve token account, the signer lamports are wrapped in it when wrapping native SOL
+    #[account(mut, token::mint = reserve_mint, token::authority = signer)]
     pub user_reserve_token_account: Box<Account<'info, TokenAccount>>,
 
-    /// User reserve token account
-    #[account(mut, token::mint = reserve_mint)]
+    /// Beneficiary token account recorded for the side, its owner receives the position token
+    #[account(mut, token::mint = reserve_mint, token::authority = beneficiary_owner)]
     pub beneficiary_token_account: Box<Account<'info, TokenAccount>>,
 
+    /// CHECK: owner of the beneficiary token account, receives the position token
+    #[account()]
+    pub beneficiary_owner: AccountInfo<'info>,
+
     /// Vault Configuration initialized
     #[account(mut,
         has_one = otc_senior_reserve_token_account,
@@ -32,6 +36,15 @@ pub struct DepositContext<'info> {
     #[account(seeds = [otc_state.key().as_ref(), b"authority".as_ref()], bump)]
     pub otc_authority: AccountInfo<'info>,
 
+    /// Global Configuration
+    #[account(seeds = [GlobalConfig::SEED], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// CHECK: treasury token account receiving the protocol fees,
+    /// only checked when a fee is charged as the treasury may not hold every reserve mint
+    #[account(mut)]
+    pub treasury_token_account: AccountInfo<'info>,
+
     // - - - - - - - - - - - - 
     // OTC Token Accounts
 
@@ -51,6 +64,17 @@ pub struct DepositContext<'info> {
     #[account(mut, token::mint = junior_tranche_mint, token::authority = otc_authority)]
     pub otc_junior_tranche_token_account: Box<Account<'info, TokenAccount>>,
     
+    // - - - - - - - - - - - - 
+    // Position Token
+
+    /// Position mint of the selected side
+    #[account(mut, mint::authority = otc_authority)]
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    /// Beneficiary owner position token account, receives the position token
+    #[account(init_if_needed, payer = signer, associated_token::mint = position_mint, associated_token::authority = beneficiary_owner)]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
     // - - - - - - - - - - - - 
     // Token Mint
 
@@ -94,6 +118,9 @@ pub struct DepositContext<'info> {
     /// Token program
     pub token_program: Program<'info, Token>,
 
+    /// Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
     /// Signer account
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -101,6 +128,26 @@ pub struct DepositContext<'info> {
 
 impl<'info> DepositContext<'info> {
 
+    fn transfer_fee_context(
+        &self,
+        is_senior: bool
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+
+        let source_reserve_token_account = if is_senior {
+            &self.otc_senior_reserve_token_account
+        } else {
+            &self.otc_junior_reserve_token_account
+        };
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: source_reserve_token_account.to_account_info(),
+                to: self.treasury_token_account.to_account_info(),
+                authority: self.otc_authority.to_account_info(),
+            },
+        )
+    }
+
     fn deposit_to_vyper_context(
         &self,
         is_senior: bool
@@ -136,11 +183,16 @@ impl<'info> DepositContext<'info> {
 #[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug)]
 pub struct DepositInputData {
     pub is_senior_side: bool,
+
+    /// wrap the deposit from the signer lamports, the reserve mint needs to be the native mint
+    pub wrap_native: bool,
 }
 
 #[allow(clippy::collapsible_else_if)]
 pub fn handler(ctx: Context<DepositContext>, input_data: DepositInputData) -> Result<()> {
-    
+
+    require!(!ctx.accounts.global_config.paused, VyperOtcErrorCode::ProgramPaused);
+
     // check that the selected side is free
     if input_data.is_senior_side {
         if ctx.accounts.otc_state.senior_side_beneficiary.is_some() {
@@ -152,9 +204,38 @@ pub fn handler(ctx: Context<DepositContext>, input_data: DepositInputData) -> Re
         }
     }
     
-    // check that the deposits are still open
+    // check that the otc is still waiting for counterparties
+    require!(ctx.accounts.otc_state.status == OtcStatus::Open, VyperOtcErrorCode::InvalidOtcStatus);
+
+    // check that the vyper plugins are still allowed by the global configuration
+    require!(
+        ctx.accounts.global_config.is_rate_program_allowed(&ctx.accounts.vyper_tranche_config.rate_program)
+            && ctx.accounts.global_config.is_redeem_logic_program_allowed(&ctx.accounts.vyper_tranche_config.redeem_logic_program),
+        VyperOtcErrorCode::PluginProgramNotAllowed
+    );
+
+    // check that the position mint belongs to the selected side
+    require!(
+        ctx.accounts.otc_state.position_side(&ctx.accounts.position_mint.key()) == Some(input_data.is_senior_side),
+        VyperOtcErrorCode::PositionMintNotFound
+    );
+
+    // check that the signer is allowed to take the selected side
+    let counterparty = if input_data.is_senior_side {
+        ctx.accounts.otc_state.senior_counterparty
+    } else {
+        ctx.accounts.otc_state.junior_counterparty
+    };
+    if let Some(counterparty) = counterparty {
+        require_keys_eq!(counterparty, ctx.accounts.signer.key(), VyperOtcErrorCode::CounterpartyNotAllowed);
+    }
+
+    // check that the deposits are open
     let clock = Clock::get()?;
-    if clock.unix_timestamp > ctx.accounts.otc_state.deposit_end {
+    if !ctx.accounts.otc_state.is_deposit_started(clock.unix_timestamp) {
+        return err!(VyperOtcErrorCode::DepositNotOpenYet);
+    }
+    if ctx.accounts.otc_state.is_deposit_expired(clock.unix_timestamp) {
         return err!(VyperOtcErrorCode::DepositClosed);
     }
 
@@ -164,6 +245,25 @@ pub fn handler(ctx: Context<DepositContext>, input_data: DepositInputData) -> Re
     } else {
         (ctx.accounts.otc_state.junior_deposit_amount, &ctx.accounts.otc_junior_reserve_token_account)
     };
+    if input_data.wrap_native {
+        require_keys_eq!(ctx.accounts.reserve_mint.key(), token::spl_token::native_mint::ID, VyperOtcErrorCode::NativeMintRequired);
+
+        // wrap the lamports in the user reserve token account, the transfer below checks the synced balance
+        invoke(
+            &system_instruction::transfer(&ctx.accounts.signer.key(), &ctx.accounts.user_reserve_token_account.key(), deposit_amount),
+            &[
+                ctx.accounts.signer.to_account_info(),
+                ctx.accounts.user_reserve_token_account.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+        token::sync_native(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            SyncNative {
+                account: ctx.accounts.user_reserve_token_account.to_account_info(),
+            },
+        ))?;
+    }
     token::transfer(CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
@@ -172,6 +272,18 @@ pub fn handler(ctx: Context<DepositContext>, input_data: DepositInputData) -> Re
             authority: ctx.accounts.signer.to_account_info(),
         },
     ), deposit_amount)?;
+
+    // mint the position token to the beneficiary owner,
+    // so that the recorded beneficiary is the one holding the claim on the side
+    token::mint_to(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.position_mint.to_account_info(),
+            to: ctx.accounts.position_token_account.to_account_info(),
+            authority: ctx.accounts.otc_authority.to_account_info(),
+        },
+        &[&ctx.accounts.otc_state.authority_seeds()]
+    ), 1)?;
     
     // save beneficiary per current side
     if input_data.is_senior_side {
@@ -180,14 +292,41 @@ pub fn handler(ctx: Context<DepositContext>, input_data: DepositInputData) -> Re
         ctx.accounts.otc_state.junior_side_beneficiary = Some(ctx.accounts.beneficiary_token_account.key());
     }
 
-    // if both sides have deposited deploy assets on vyper
-    if ctx.accounts.otc_state.senior_side_beneficiary.is_some() && ctx.accounts.otc_state.junior_side_beneficiary.is_some() {
+    // if both sides have deposited charge the protocol fee and deploy assets on vyper
+    let mut senior_fee_amount = 0;
+    let mut junior_fee_amount = 0;
+    if ctx.accounts.otc_state.both_sides_taken() {
+        senior_fee_amount = ctx.accounts.otc_state.fee_amount(ctx.accounts.otc_state.senior_deposit_amount)?;
+        junior_fee_amount = ctx.accounts.otc_state.fee_amount(ctx.accounts.otc_state.junior_deposit_amount)?;
+
+        if senior_fee_amount > 0 || junior_fee_amount > 0 {
+            let treasury_token_account = Account::<TokenAccount>::try_from(&ctx.accounts.treasury_token_account)?;
+            require_keys_eq!(treasury_token_account.mint, ctx.accounts.reserve_mint.key(), ErrorCode::ConstraintTokenMint);
+            require_keys_eq!(treasury_token_account.owner, ctx.accounts.global_config.treasury, ErrorCode::ConstraintTokenOwner);
+        }
+        if senior_fee_amount > 0 {
+            token::transfer(
+                ctx.accounts
+                    .transfer_fee_context(true)
+                    .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
+                senior_fee_amount,
+            )?;
+        }
+        if junior_fee_amount > 0 {
+            token::transfer(
+                ctx.accounts
+                    .transfer_fee_context(false)
+                    .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
+                junior_fee_amount,
+            )?;
+        }
+
         vyper_core::cpi::deposit(
             ctx.accounts
                 .deposit_to_vyper_context(true)
                 .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
             vyper_core::instructions::DepositInput {
-                reserve_quantity: [ctx.accounts.otc_state.senior_deposit_amount, 0],
+                reserve_quantity: [ctx.accounts.otc_state.senior_deposit_amount - senior_fee_amount, 0],
             },
         )?;
     
@@ -196,11 +335,42 @@ pub fn handler(ctx: Context<DepositContext>, input_data: DepositInputData) -> Re
                 .deposit_to_vyper_context(false)
                 .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
             vyper_core::instructions::DepositInput {
-                reserve_quantity: [0, ctx.accounts.otc_state.junior_deposit_amount],
+                reserve_quantity: [0, ctx.accounts.otc_state.junior_deposit_amount - junior_fee_amount],
             },
         )?;
+
+        ctx.accounts.otc_state.status = OtcStatus::Matched;
     }
 
+    ctx.accounts.otc_senior_reserve_token_account.reload()?;
+    ctx.accounts.otc_junior_reserve_token_account.reload()?;
+
+    emit!(DepositEvent {
+        otc_state: ctx.accounts.otc_state.key(),
+        is_senior_side: input_data.is_senior_side,
+        beneficiary: ctx.accounts.beneficiary_token_account.key(),
+        position_token_account: ctx.accounts.position_token_account.key(),
+        amount: deposit_amount,
+        both_sides_taken: ctx.accounts.otc_state.both_sides_taken(),
+        senior_fee_amount,
+        junior_fee_amount,
+        senior_reserve_amount: ctx.accounts.otc_senior_reserve_token_account.amount,
+        junior_reserve_amount: ctx.accounts.otc_junior_reserve_token_account.amount,
+    });
 
     Ok(())
 }
+
+#[event]
+pub struct DepositEvent {
+    pub otc_state: Pubkey,
+    pub is_senior_side: bool,
+    pub beneficiary: Pubkey,
+    pub position_token_account: Pubkey,
+    pub amount: u64,
+    pub both_sides_taken: bool,
+    pub senior_fee_amount: u64,
+    pub junior_fee_amount: u64,
+    pub senior_reserve_amount: u64,
+    pub junior_reserve_amount: u64,
+}
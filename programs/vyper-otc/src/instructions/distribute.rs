@@ -0,0 +1,149 @@
+use crate::{
+    errors::{ VyperOtcErrorCode },
+    state::{ OtcState, OtcStatus }
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, self, Transfer};
+
+#[derive(Accounts)]
+pub struct DistributeContext<'info> {
+
+    /// Vault Configuration initialized
+    #[account(mut, has_one = otc_senior_reserve_token_account, has_one = otc_junior_reserve_token_account)]
+    pub otc_state: Box<Account<'info, OtcState>>,
+
+    /// CHECK: Vault Configuration Authority
+    #[account(seeds = [otc_state.key().as_ref(), b"authority".as_ref()], bump)]
+    pub otc_authority: AccountInfo<'info>,
+
+    /// Beneficiary token account recorded for the side, receives the redeemed assets
+    #[account(mut)]
+    pub beneficiary_token_account: Box<Account<'info, TokenAccount>>,
+
+    // - - - - - - - - - - - - 
+    // Position Token
+
+    /// Position mint of the side to distribute
+    #[account()]
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    /// Position token account of the beneficiary token account owner
+    #[account(token::mint = position_mint)]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    // - - - - - - - - - - - - 
+    // OTC Token Accounts
+
+    /// Vault senior reserve token account
+    #[account(mut, token::authority = otc_authority)]
+    pub otc_senior_reserve_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Vault junior reserve token account
+    #[account(mut, token::authority = otc_authority)]
+    pub otc_junior_reserve_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Signer account, anyone can distribute
+    pub signer: Signer<'info>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug)]
+pub struct DistributeInputData {
+    pub is_senior_side: bool,
+}
+
+pub fn handler(ctx: Context<DistributeContext>, input_data: DistributeInputData) -> Result<()> {
+    
+    // check that assets can be redeemed
+    if let OtcStatus::Open | OtcStatus::Matched = ctx.accounts.otc_state.status {
+        return err!(VyperOtcErrorCode::SettleNotExecutedYet);
+    }
+    require!(
+        ctx.accounts.otc_state.status == OtcStatus::Settled || ctx.accounts.otc_state.status == OtcStatus::Claimed,
+        VyperOtcErrorCode::InvalidOtcStatus
+    );
+
+    let is_senior = input_data.is_senior_side;
+    require!(
+        ctx.accounts.otc_state.position_side(&ctx.accounts.position_mint.key()) == Some(is_senior),
+        VyperOtcErrorCode::PositionMintNotFound
+    );
+    let (side_beneficiary, claimed_amount) = if is_senior {
+        (ctx.accounts.otc_state.senior_side_beneficiary, ctx.accounts.otc_state.senior_claimed_amount)
+    } else {
+        (ctx.accounts.otc_state.junior_side_beneficiary, ctx.accounts.otc_state.junior_claimed_amount)
+    };
+    if claimed_amount.is_some() {
+        return err!(VyperOtcErrorCode::SideAlreadyClaimed);
+    }
+
+    // pay only the recorded beneficiary, and only while its owner still holds the position token,
+    // a position token moved outside transfer_position has to be claimed by its holder
+    if side_beneficiary != Some(ctx.accounts.beneficiary_token_account.key()) {
+        return err!(VyperOtcErrorCode::BeneficiaryNotFound);
+    }
+    require_keys_eq!(
+        ctx.accounts.position_token_account.owner,
+        ctx.accounts.beneficiary_token_account.owner,
+        VyperOtcErrorCode::PositionTokenNotHeld
+    );
+    require_gte!(ctx.accounts.position_token_account.amount, 1, VyperOtcErrorCode::PositionTokenNotHeld);
+
+    // only the amount redeemed for the side is paid out,
+    // the position token can't be burned without its holder and is left worthless
+    let (amount, source_account) = if is_senior {
+        (ctx.accounts.otc_state.senior_redeemed_amount, &ctx.accounts.otc_senior_reserve_token_account)
+    } else {
+        (ctx.accounts.otc_state.junior_redeemed_amount, &ctx.accounts.otc_junior_reserve_token_account)
+    };
+
+    // transfer assets
+    token::transfer(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: source_account.to_account_info(),
+            to: ctx.accounts.beneficiary_token_account.to_account_info(),
+            authority: ctx.accounts.otc_authority.to_account_info(),
+        },
+        &[&ctx.accounts.otc_state.authority_seeds()]
+    ), amount)?;
+
+    // save claimed amount
+    let otc_state = &mut ctx.accounts.otc_state;
+    if is_senior {
+        otc_state.senior_claimed_amount = Some(amount);
+    } else {
+        otc_state.junior_claimed_amount = Some(amount);
+    }
+    if otc_state.senior_claimed_amount.is_some() && otc_state.junior_claimed_amount.is_some() {
+        otc_state.status = OtcStatus::Claimed;
+    }
+
+    ctx.accounts.otc_senior_reserve_token_account.reload()?;
+    ctx.accounts.otc_junior_reserve_token_account.reload()?;
+
+    emit!(DistributeEvent {
+        otc_state: ctx.accounts.otc_state.key(),
+        is_senior_side: is_senior,
+        beneficiary: ctx.accounts.beneficiary_token_account.key(),
+        signer: ctx.accounts.signer.key(),
+        amount,
+        senior_reserve_amount: ctx.accounts.otc_senior_reserve_token_account.amount,
+        junior_reserve_amount: ctx.accounts.otc_junior_reserve_token_account.amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct DistributeEvent {
+    pub otc_state: Pubkey,
+    pub is_senior_side: bool,
+    pub beneficiary: Pubkey,
+    pub signer: Pubkey,
+    pub amount: u64,
+    pub senior_reserve_amount: u64,
+    pub junior_reserve_amount: u64,
+}
@@ -0,0 +1,284 @@
+use crate::{
+    errors::{ VyperOtcErrorCode },
+    instructions::unwind::rebalance_side_reserves,
+    state::{ GlobalConfig, OtcState, OtcStatus }
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use vyper_core::{state::{TrancheConfig}, program::VyperCore};
+
+#[derive(Accounts)]
+pub struct EarlyTerminateContext<'info> {
+
+    /// Senior position holder
+    pub senior_signer: Signer<'info>,
+
+    /// Junior position holder
+    pub junior_signer: Signer<'info>,
+
+    /// Vault Configuration initialized
+    #[account(mut,
+        has_one = otc_senior_reserve_token_account,
+        has_one = otc_junior_reserve_token_account,
+        has_one = otc_senior_tranche_token_account,
+        has_one = otc_junior_tranche_token_account,
+        has_one = senior_position_mint,
+        has_one = junior_position_mint,
+        has_one = otc_authority,
+        has_one = vyper_core,
+        has_one = vyper_tranche_config)]
+    pub otc_state: Box<Account<'info, OtcState>>,
+
+    /// CHECK: Vault Configuration Authority
+    #[account(seeds = [otc_state.key().as_ref(), b"authority".as_ref()], bump)]
+    pub otc_authority: AccountInfo<'info>,
+
+    /// Global Configuration
+    #[account(seeds = [GlobalConfig::SEED], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    // - - - - - - - - - - - -
+    // Position Token
+
+    /// Senior position mint
+    #[account()]
+    pub senior_position_mint: Box<Account<'info, Mint>>,
+
+    /// Senior signer position token account
+    #[account(token::mint = senior_position_mint, token::authority = senior_signer)]
+    pub senior_position_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Junior position mint
+    #[account()]
+    pub junior_position_mint: Box<Account<'info, Mint>>,
+
+    /// Junior signer position token account
+    #[account(token::mint = junior_position_mint, token::authority = junior_signer)]
+    pub junior_position_token_account: Box<Account<'info, TokenAccount>>,
+
+    // - - - - - - - - - - - - 
+    // OTC Token Accounts
+
+    /// OTC senior reserve token account
+    #[account(mut, token::mint = reserve_mint, token::authority = otc_authority)]
+    pub otc_senior_reserve_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// OTC junior reserve token account
+    #[account(mut, token::mint = reserve_mint, token::authority = otc_authority)]
+    pub otc_junior_reserve_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// OTC senior tranche token account
+    #[account(mut, token::mint = senior_tranche_mint, token::authority = otc_authority)]
+    pub otc_senior_tranche_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// OTC junior tranche token account
+    #[account(mut, token::mint = junior_tranche_mint, token::authority = otc_authority)]
+    pub otc_junior_tranche_token_account: Box<Account<'info, TokenAccount>>,
+
+    // - - - - - - - - - - - - 
+    // Token Mint
+
+    /// Reserve Token mint
+    #[account()]
+    pub reserve_mint: Box<Account<'info, Mint>>,
+
+    /// Senior Tranche Token mint
+    #[account(mut)]
+    pub senior_tranche_mint: Box<Account<'info, Mint>>,
+
+    /// Junior Tranche Token mint
+    #[account(mut)]
+    pub junior_tranche_mint: Box<Account<'info, Mint>>,
+
+    // - - - - - - - - - - - - 
+    // Vyper Accounts
+
+    /// Vyper Core Tranche Configuration
+    #[account(mut, has_one = reserve_mint, has_one = senior_tranche_mint, has_one = junior_tranche_mint)]
+    pub vyper_tranche_config: Box<Account<'info, TrancheConfig>>,
+
+    /// Vyper Core tranche configuration authority
+    /// CHECK:
+    #[account()]
+    pub vyper_tranche_authority: AccountInfo<'info>,
+
+    /// Vyper Core reserve token account
+    #[account(mut)]
+    pub vyper_reserve: Box<Account<'info, TokenAccount>>,
+
+    /// Vyper Core program
+    pub vyper_core: Program<'info, VyperCore>,
+
+    /// Rent program
+    pub rent: Sysvar<'info, Rent>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug)]
+pub struct EarlyTerminateInputData {
+    /// share of the redeemed collateral paid to the senior side, in basis points,
+    /// the split of the current vyper fair value if missing
+    pub senior_split_bps: Option<u16>,
+}
+
+impl EarlyTerminateInputData {
+    pub const MAX_SPLIT_BPS: u16 = 10_000;
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(senior_split_bps) = self.senior_split_bps {
+            require!(senior_split_bps <= EarlyTerminateInputData::MAX_SPLIT_BPS, VyperOtcErrorCode::InvalidSplit);
+        }
+        Ok(())
+    }
+}
+
+impl<'info> EarlyTerminateContext<'info> {
+    
+    fn to_vyper_redeem_context(
+        &self, is_senior: bool
+    ) -> CpiContext<'_, '_, '_, 'info, vyper_core::cpi::accounts::RedeemContext<'info>> {
+
+        let dest_reserve_account = if is_senior {
+            &self.otc_senior_reserve_token_account
+        } else {
+            &self.otc_junior_reserve_token_account
+        };
+
+        CpiContext::new(
+            self.vyper_core.to_account_info(),
+            vyper_core::cpi::accounts::RedeemContext {
+                signer: self.otc_authority.to_account_info(),
+                tranche_config: self.vyper_tranche_config.to_account_info(),
+                tranche_authority: self.vyper_tranche_authority.to_account_info(),
+                reserve: self.vyper_reserve.to_account_info(),
+                user_reserve_token: dest_reserve_account.to_account_info(),
+                senior_tranche_mint: self.senior_tranche_mint.to_account_info(),
+                junior_tranche_mint: self.junior_tranche_mint.to_account_info(),
+                senior_tranche_source: self.otc_senior_tranche_token_account.to_account_info(),
+                junior_tranche_source: self.otc_junior_tranche_token_account.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                rent: self.rent.to_account_info(),
+            },
+        )
+    }
+}
+
+pub fn handler(ctx: Context<EarlyTerminateContext>, input_data: EarlyTerminateInputData) -> Result<()> {
+
+    input_data.validate()?;
+
+    // both position holders agree to terminate before the settlement,
+    // an agreed split only returns funds and stays available while paused,
+    // the fair value split relies on the rate plugin like the settlement
+    require!(
+        input_data.senior_split_bps.is_some() || !ctx.accounts.global_config.paused,
+        VyperOtcErrorCode::ProgramPaused
+    );
+    let clock = Clock::get()?;
+    if ctx.accounts.otc_state.is_settle_available(clock.unix_timestamp) {
+        return err!(VyperOtcErrorCode::EarlyTerminationNotAvailable);
+    }
+    require!(ctx.accounts.otc_state.status == OtcStatus::Matched, VyperOtcErrorCode::InvalidOtcStatus);
+    require_gte!(ctx.accounts.senior_position_token_account.amount, 1, VyperOtcErrorCode::PositionTokenNotHeld);
+    require_gte!(ctx.accounts.junior_position_token_account.amount, 1, VyperOtcErrorCode::PositionTokenNotHeld);
+
+    let senior_tranche_amount = ctx.accounts.otc_senior_tranche_token_account.amount;
+    let junior_tranche_amount = ctx.accounts.otc_junior_tranche_token_account.amount;
+    let senior_reserve_amount_before = ctx.accounts.otc_senior_reserve_token_account.amount;
+    let junior_reserve_amount_before = ctx.accounts.otc_junior_reserve_token_account.amount;
+
+    // redeem assets, at the current vyper fair value
+    vyper_core::cpi::redeem(
+        ctx.accounts
+            .to_vyper_redeem_context(true)
+            .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
+        vyper_core::instructions::RedeemInput {
+            tranche_quantity: [senior_tranche_amount, 0],
+        },
+    )?;
+    vyper_core::cpi::redeem(
+        ctx.accounts
+            .to_vyper_redeem_context(false)
+            .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
+        vyper_core::instructions::RedeemInput {
+            tranche_quantity: [0, junior_tranche_amount],
+        },
+    )?;
+
+    ctx.accounts.otc_senior_reserve_token_account.reload()?;
+    ctx.accounts.otc_junior_reserve_token_account.reload()?;
+    // the redeem cpis refresh the fair values on the tranche config, read them back
+    ctx.accounts.vyper_tranche_config.reload()?;
+
+    let senior_received_amount = ctx.accounts.otc_senior_reserve_token_account.amount
+        .checked_sub(senior_reserve_amount_before)
+        .ok_or(VyperOtcErrorCode::MathError)?;
+    let junior_received_amount = ctx.accounts.otc_junior_reserve_token_account.amount
+        .checked_sub(junior_reserve_amount_before)
+        .ok_or(VyperOtcErrorCode::MathError)?;
+    let total_redeemed_amount = senior_received_amount
+        .checked_add(junior_received_amount)
+        .ok_or(VyperOtcErrorCode::MathError)?;
+
+    // apply the agreed split, if any
+    let senior_redeemed_amount = match input_data.senior_split_bps {
+        Some(senior_split_bps) => {
+            let senior_redeemed_amount = ((total_redeemed_amount as u128) * (senior_split_bps as u128)
+                / EarlyTerminateInputData::MAX_SPLIT_BPS as u128) as u64;
+            rebalance_side_reserves(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.otc_senior_reserve_token_account.to_account_info(),
+                ctx.accounts.otc_junior_reserve_token_account.to_account_info(),
+                ctx.accounts.otc_authority.to_account_info(),
+                &ctx.accounts.otc_state.authority_seeds(),
+                senior_received_amount,
+                senior_redeemed_amount,
+            )?;
+            senior_redeemed_amount
+        }
+        None => {
+            ctx.accounts.otc_state.settle_reserve_fair_value = ctx.accounts.vyper_tranche_config.tranche_data.reserve_fair_value.value;
+            ctx.accounts.otc_state.settle_tranche_fair_value = ctx.accounts.vyper_tranche_config.tranche_data.tranche_fair_value.value;
+            senior_received_amount
+        }
+    };
+
+    // the sides are then claimed as after a settlement, the settle bounty is refunded on close
+    ctx.accounts.otc_state.senior_redeemed_amount = senior_redeemed_amount;
+    ctx.accounts.otc_state.junior_redeemed_amount = total_redeemed_amount - senior_redeemed_amount;
+    ctx.accounts.otc_state.settle_timestamp = Some(clock.unix_timestamp);
+    ctx.accounts.otc_state.status = OtcStatus::Settled;
+
+    emit!(EarlyTerminateEvent {
+        otc_state: ctx.accounts.otc_state.key(),
+        senior_signer: ctx.accounts.senior_signer.key(),
+        junior_signer: ctx.accounts.junior_signer.key(),
+        senior_split_bps: input_data.senior_split_bps,
+        settle_timestamp: clock.unix_timestamp,
+        senior_tranche_amount,
+        junior_tranche_amount,
+        senior_redeemed_amount: ctx.accounts.otc_state.senior_redeemed_amount,
+        junior_redeemed_amount: ctx.accounts.otc_state.junior_redeemed_amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct EarlyTerminateEvent {
+    pub otc_state: Pubkey,
+    pub senior_signer: Pubkey,
+    pub junior_signer: Pubkey,
+    pub senior_split_bps: Option<u16>,
+    pub settle_timestamp: i64,
+    pub senior_tranche_amount: u64,
+    pub junior_tranche_amount: u64,
+    pub senior_redeemed_amount: u64,
+    pub junior_redeemed_amount: u64,
+}
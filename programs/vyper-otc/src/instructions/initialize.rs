@@ -1,8 +1,8 @@
 use crate::{
     errors::{ VyperOtcErrorCode },
-    state::{ OtcState }
+    state::{ GlobalConfig, OtcState, OtcStatus }
 };
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::{program::invoke, system_instruction}};
 use anchor_spl::token::{Mint, Token, TokenAccount};
 use vyper_core::{state::{OwnerRestrictedIxFlags, TrancheConfig}, program::VyperCore};
 
@@ -17,6 +17,10 @@ pub struct InitializeContext<'info> {
     #[account(seeds = [otc_state.key().as_ref(), b"authority".as_ref()], bump)]
     pub otc_authority: AccountInfo<'info>,
 
+    /// Global Configuration
+    #[account(seeds = [GlobalConfig::SEED], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
     // - - - - - - - - - - - - 
     // OTC Token Accounts
 
@@ -35,6 +39,17 @@ pub struct InitializeContext<'info> {
     /// OTC junior tranche token account
     #[account(init, payer = signer, token::mint = junior_tranche_mint, token::authority = otc_authority)]
     pub otc_junior_tranche_token_account: Box<Account<'info, TokenAccount>>,
+
+    // - - - - - - - - - - - - 
+    // Position Mints
+
+    /// Senior side position mint, one token minted to the senior beneficiary owner
+    #[account(init, payer = signer, seeds = [otc_state.key().as_ref(), b"senior_position".as_ref()], bump, mint::decimals = 0, mint::authority = otc_authority)]
+    pub senior_position_mint: Box<Account<'info, Mint>>,
+
+    /// Junior side position mint, one token minted to the junior beneficiary owner
+    #[account(init, payer = signer, seeds = [otc_state.key().as_ref(), b"junior_position".as_ref()], bump, mint::decimals = 0, mint::authority = otc_authority)]
+    pub junior_position_mint: Box<Account<'info, Mint>>,
     
     // - - - - - - - - - - - - 
     // Token Mint
@@ -82,11 +97,23 @@ pub struct InitializeInputData {
     pub deposit_start: Option<i64>,
     pub deposit_end: i64,
     pub settle_start: i64,
+
+    /// matched otcs not settled by this timestamp can be unwound,
+    /// defaults to `settle_start` plus the global settle grace period
+    pub settle_deadline: Option<i64>,
+
+    pub senior_counterparty: Option<Pubkey>,
+    pub junior_counterparty: Option<Pubkey>,
+
+    /// lamports paid by the signer to the first settle caller
+    pub settle_bounty: u64,
 }
 
 pub fn handler(ctx: Context<InitializeContext>, input_data: InitializeInputData) -> Result<()> {
     let clock = Clock::get()?;
 
+    require!(!ctx.accounts.global_config.paused, VyperOtcErrorCode::ProgramPaused);
+
     // tranche config owner needs to be the vault authority
     require_keys_eq!(
         ctx.accounts.vyper_tranche_config.owner.key(),
@@ -112,6 +139,38 @@ pub fn handler(ctx: Context<InitializeContext>, input_data: InitializeInputData)
     }
     require_gt!(input_data.settle_start, input_data.deposit_end, VyperOtcErrorCode::InitializationError);
 
+    // every otc can be unwound at some point, collateral can't stay locked
+    let global_config = &ctx.accounts.global_config;
+    let settle_deadline = match input_data.settle_deadline {
+        Some(settle_deadline) => settle_deadline,
+        None => input_data
+            .settle_start
+            .checked_add(global_config.settle_grace_period)
+            .ok_or(VyperOtcErrorCode::MathError)?,
+    };
+    require_gt!(settle_deadline, input_data.settle_start, VyperOtcErrorCode::InitializationError);
+
+    // require duration and vyper plugins allowed by the global configuration
+    let duration = input_data.settle_start - input_data.deposit_start.unwrap_or(clock.unix_timestamp);
+    require!(global_config.is_duration_allowed(duration), VyperOtcErrorCode::DurationNotAllowed);
+    require!(
+        global_config.is_rate_program_allowed(&ctx.accounts.vyper_tranche_config.rate_program)
+            && global_config.is_redeem_logic_program_allowed(&ctx.accounts.vyper_tranche_config.redeem_logic_program),
+        VyperOtcErrorCode::PluginProgramNotAllowed
+    );
+
+    // fund the settle bounty, held by the otc state
+    if input_data.settle_bounty > 0 {
+        invoke(
+            &system_instruction::transfer(&ctx.accounts.signer.key(), &ctx.accounts.otc_state.key(), input_data.settle_bounty),
+            &[
+                ctx.accounts.signer.to_account_info(),
+                ctx.accounts.otc_state.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+
     // create otc state
     let otc_state = &mut ctx.accounts.otc_state;
 
@@ -121,21 +180,29 @@ pub fn handler(ctx: Context<InitializeContext>, input_data: InitializeInputData)
         otc_state.deposit_start = deposit_start;
     } else {
         // set deposit start to now
-        otc_state.deposit_start = Clock::get()?.unix_timestamp;
+        otc_state.deposit_start = clock.unix_timestamp;
     }
     otc_state.deposit_end = input_data.deposit_end;
     otc_state.settle_start = input_data.settle_start;
-    otc_state.settle_executed = false;
+    otc_state.settle_deadline = settle_deadline;
+    otc_state.status = OtcStatus::Open;
     otc_state.senior_deposit_amount = input_data.senior_deposit_amount;
     otc_state.junior_deposit_amount = input_data.junior_deposit_amount;
+    otc_state.fee_bps = ctx.accounts.global_config.fee_bps;
+    otc_state.settle_bounty = input_data.settle_bounty;
+    otc_state.senior_counterparty = input_data.senior_counterparty;
+    otc_state.junior_counterparty = input_data.junior_counterparty;
 
     // accounts
     otc_state.otc_senior_reserve_token_account = ctx.accounts.otc_senior_reserve_token_account.key();
     otc_state.otc_junior_reserve_token_account = ctx.accounts.otc_junior_reserve_token_account.key();
     otc_state.otc_senior_tranche_token_account = ctx.accounts.otc_senior_tranche_token_account.key();
     otc_state.otc_junior_tranche_token_account = ctx.accounts.otc_junior_tranche_token_account.key();
+    otc_state.senior_position_mint = ctx.accounts.senior_position_mint.key();
+    otc_state.junior_position_mint = ctx.accounts.junior_position_mint.key();
     otc_state.vyper_tranche_config = ctx.accounts.vyper_tranche_config.key();
     otc_state.vyper_core = ctx.accounts.vyper_core.key();
+    otc_state.creator = ctx.accounts.signer.key();
     otc_state.otc_authority = ctx.accounts.otc_authority.key();
     otc_state.authority_seed = otc_state.key();
     otc_state.authority_bump = [*ctx
@@ -146,16 +213,24 @@ pub fn handler(ctx: Context<InitializeContext>, input_data: InitializeInputData)
 
     emit!(InitializeEvent {
         otc_state: ctx.accounts.otc_state.key(),
+        creator: ctx.accounts.signer.key(),
         senior_deposit_amount: input_data.senior_deposit_amount,
         junior_deposit_amount: input_data.junior_deposit_amount,
+        fee_bps: ctx.accounts.global_config.fee_bps,
         deposit_expiration: input_data.deposit_end,
         settle_available_from: input_data.settle_start,
+        settle_deadline,
+        senior_counterparty: input_data.senior_counterparty,
+        junior_counterparty: input_data.junior_counterparty,
+        senior_position_mint: ctx.accounts.senior_position_mint.key(),
+        junior_position_mint: ctx.accounts.junior_position_mint.key(),
+        settle_bounty: input_data.settle_bounty,
     });
 
     Ok(())
 }
 
-fn get_version_arr() -> [u8; 3] {
+pub(crate) fn get_version_arr() -> [u8; 3] {
     [
         env!("CARGO_PKG_VERSION_MAJOR")
             .parse::<u8>()
@@ -172,8 +247,16 @@ fn get_version_arr() -> [u8; 3] {
 #[event]
 pub struct InitializeEvent {
     pub otc_state: Pubkey,
+    pub creator: Pubkey,
     pub senior_deposit_amount: u64,
     pub junior_deposit_amount: u64,
+    pub fee_bps: u16,
     pub deposit_expiration: i64,
     pub settle_available_from: i64,
+    pub settle_deadline: i64,
+    pub senior_counterparty: Option<Pubkey>,
+    pub junior_counterparty: Option<Pubkey>,
+    pub senior_position_mint: Pubkey,
+    pub junior_position_mint: Pubkey,
+    pub settle_bounty: u64,
 }
\ No newline at end of file
@@ -0,0 +1,107 @@
+use crate::{
+    errors::{ VyperOtcErrorCode },
+    instructions::initialize::get_version_arr,
+    program::VyperOtc,
+    state::{ GlobalConfig }
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeGlobalConfigContext<'info> {
+
+    /// Global Configuration initialized
+    #[account(init, payer = signer, space = GlobalConfig::LEN, seeds = [GlobalConfig::SEED], bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Vyper OTC program
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, VyperOtc>,
+
+    /// Vyper OTC program data, only the upgrade authority can initialize the global configuration
+    #[account(constraint = program_data.upgrade_authority_address == Some(signer.key()))]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Signer account
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct GlobalConfigInputData {
+
+    /// admin set on initialization, on update it's proposed and takes over once it accepts
+    pub admin: Pubkey,
+
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+    pub min_duration: Option<i64>,
+    pub max_duration: Option<i64>,
+    pub settle_grace_period: i64,
+    pub allowed_rate_programs: Vec<Pubkey>,
+    pub allowed_redeem_logic_programs: Vec<Pubkey>,
+}
+
+impl GlobalConfigInputData {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.fee_bps <= GlobalConfig::MAX_FEE_BPS, VyperOtcErrorCode::InvalidGlobalConfig);
+        if let (Some(min_duration), Some(max_duration)) = (self.min_duration, self.max_duration) {
+            require_gte!(max_duration, min_duration, VyperOtcErrorCode::InvalidGlobalConfig);
+        }
+        require_gt!(self.settle_grace_period, 0, VyperOtcErrorCode::InvalidGlobalConfig);
+        require!(self.allowed_rate_programs.len() <= GlobalConfig::MAX_ALLOWED_PROGRAMS, VyperOtcErrorCode::InvalidGlobalConfig);
+        require!(self.allowed_redeem_logic_programs.len() <= GlobalConfig::MAX_ALLOWED_PROGRAMS, VyperOtcErrorCode::InvalidGlobalConfig);
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<InitializeGlobalConfigContext>, input_data: GlobalConfigInputData) -> Result<()> {
+
+    input_data.validate()?;
+
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.admin = input_data.admin;
+    global_config.pending_admin = None;
+    global_config.fee_bps = input_data.fee_bps;
+    global_config.treasury = input_data.treasury;
+    global_config.min_duration = input_data.min_duration;
+    global_config.max_duration = input_data.max_duration;
+    global_config.settle_grace_period = input_data.settle_grace_period;
+    global_config.allowed_rate_programs = input_data.allowed_rate_programs.clone();
+    global_config.allowed_redeem_logic_programs = input_data.allowed_redeem_logic_programs.clone();
+    global_config.paused = false;
+    global_config.bump = *ctx
+        .bumps
+        .get("global_config")
+        .ok_or(VyperOtcErrorCode::InitializationError)?;
+    global_config.version = get_version_arr();
+
+    emit!(GlobalConfigInitializeEvent {
+        global_config: ctx.accounts.global_config.key(),
+        admin: input_data.admin,
+        fee_bps: input_data.fee_bps,
+        treasury: input_data.treasury,
+        min_duration: input_data.min_duration,
+        max_duration: input_data.max_duration,
+        settle_grace_period: input_data.settle_grace_period,
+        allowed_rate_programs: input_data.allowed_rate_programs,
+        allowed_redeem_logic_programs: input_data.allowed_redeem_logic_programs,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct GlobalConfigInitializeEvent {
+    pub global_config: Pubkey,
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+    pub min_duration: Option<i64>,
+    pub max_duration: Option<i64>,
+    pub settle_grace_period: i64,
+    pub allowed_rate_programs: Vec<Pubkey>,
+    pub allowed_redeem_logic_programs: Vec<Pubkey>,
+}
@@ -5,10 +5,30 @@ pub mod settle;
 pub mod claim;
 pub mod withdraw;
 pub mod close;
+pub mod cancel;
+pub mod initialize_global_config;
+pub mod update_global_config;
+pub mod set_paused;
+pub mod transfer_position;
+pub mod update_beneficiary;
+pub mod distribute;
+pub mod unwind;
+pub mod early_terminate;
+pub mod accept_admin;
 
 pub use initialize::*;
 pub use deposit::*;
 pub use settle::*;
 pub use claim::*;
 pub use withdraw::*;
-pub use close::*;
\ No newline at end of file
+pub use close::*;
+pub use cancel::*;
+pub use initialize_global_config::*;
+pub use update_global_config::*;
+pub use set_paused::*;
+pub use transfer_position::*;
+pub use update_beneficiary::*;
+pub use distribute::*;
+pub use unwind::*;
+pub use early_terminate::*;
+pub use accept_admin::*;
\ No newline at end of file
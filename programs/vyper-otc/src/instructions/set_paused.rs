@@ -0,0 +1,31 @@
+use crate::state::{ GlobalConfig };
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPausedContext<'info> {
+
+    /// Global Configuration
+    #[account(mut, seeds = [GlobalConfig::SEED], bump = global_config.bump, has_one = admin)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Global Configuration admin
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetPausedContext>, paused: bool) -> Result<()> {
+
+    ctx.accounts.global_config.paused = paused;
+
+    emit!(PauseEvent {
+        global_config: ctx.accounts.global_config.key(),
+        paused,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PauseEvent {
+    pub global_config: Pubkey,
+    pub paused: bool,
+}
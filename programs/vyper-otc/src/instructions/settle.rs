@@ -1,6 +1,6 @@
 use crate::{
     errors::{ VyperOtcErrorCode },
-    state::{ OtcState }
+    state::{ GlobalConfig, OtcState, OtcStatus }
 };
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
@@ -28,6 +28,10 @@ pub struct RedeemContext<'info> {
     #[account(seeds = [otc_state.key().as_ref(), b"authority".as_ref()], bump)]
     pub otc_authority: AccountInfo<'info>,
 
+    /// Global Configuration
+    #[account(seeds = [GlobalConfig::SEED], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
     // - - - - - - - - - - - - 
     // OTC Token Accounts
 
@@ -126,15 +130,25 @@ impl<'info> RedeemContext<'info> {
 
 
 pub fn handler(ctx: Context<RedeemContext>) -> Result<()> {
-    
+
+    require!(!ctx.accounts.global_config.paused, VyperOtcErrorCode::ProgramPaused);
+
     // check that assets can be redeemed
     let clock = Clock::get()?;
-    if clock.unix_timestamp < ctx.accounts.otc_state.settle_start {
+    if !ctx.accounts.otc_state.is_settle_available(clock.unix_timestamp) {
         return err!(VyperOtcErrorCode::OtcClosed);
     }
 
     // check if settlement has not been executed
-    require!(!ctx.accounts.otc_state.settle_executed, VyperOtcErrorCode::SettleAlreadyExecuted);
+    if let OtcStatus::Settled | OtcStatus::Claimed = ctx.accounts.otc_state.status {
+        return err!(VyperOtcErrorCode::SettleAlreadyExecuted);
+    }
+    require!(ctx.accounts.otc_state.status == OtcStatus::Matched, VyperOtcErrorCode::InvalidOtcStatus);
+
+    let senior_tranche_amount = ctx.accounts.otc_senior_tranche_token_account.amount;
+    let junior_tranche_amount = ctx.accounts.otc_junior_tranche_token_account.amount;
+    let senior_reserve_amount_before = ctx.accounts.otc_senior_reserve_token_account.amount;
+    let junior_reserve_amount_before = ctx.accounts.otc_junior_reserve_token_account.amount;
 
     // redeem assets
     vyper_core::cpi::redeem(
@@ -142,7 +156,7 @@ pub fn handler(ctx: Context<RedeemContext>) -> Result<()> {
             .to_vyper_redeem_context(true)
             .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
         vyper_core::instructions::RedeemInput {
-            tranche_quantity: [ctx.accounts.otc_senior_tranche_token_account.amount, 0],
+            tranche_quantity: [senior_tranche_amount, 0],
         },
     )?;
     vyper_core::cpi::redeem(
@@ -150,11 +164,68 @@ pub fn handler(ctx: Context<RedeemContext>) -> Result<()> {
             .to_vyper_redeem_context(false)
             .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
         vyper_core::instructions::RedeemInput {
-            tranche_quantity: [0, ctx.accounts.otc_junior_tranche_token_account.amount],
+            tranche_quantity: [0, junior_tranche_amount],
         },
     )?;
 
-    ctx.accounts.otc_state.settle_executed = true;
+    ctx.accounts.otc_senior_reserve_token_account.reload()?;
+    ctx.accounts.otc_junior_reserve_token_account.reload()?;
+    // the redeem cpis refresh the fair values on the tranche config, read them back
+    ctx.accounts.vyper_tranche_config.reload()?;
+
+    // save the amounts redeemed for each side, claims will pay exactly these
+    ctx.accounts.otc_state.senior_redeemed_amount = ctx.accounts.otc_senior_reserve_token_account.amount
+        .checked_sub(senior_reserve_amount_before)
+        .ok_or(VyperOtcErrorCode::MathError)?;
+    ctx.accounts.otc_state.junior_redeemed_amount = ctx.accounts.otc_junior_reserve_token_account.amount
+        .checked_sub(junior_reserve_amount_before)
+        .ok_or(VyperOtcErrorCode::MathError)?;
+
+    // keep the fair values vyper-core redeemed at
+    ctx.accounts.otc_state.settle_timestamp = Some(clock.unix_timestamp);
+    ctx.accounts.otc_state.settle_reserve_fair_value = ctx.accounts.vyper_tranche_config.tranche_data.reserve_fair_value.value;
+    ctx.accounts.otc_state.settle_tranche_fair_value = ctx.accounts.vyper_tranche_config.tranche_data.tranche_fair_value.value;
+
+    ctx.accounts.otc_state.status = OtcStatus::Settled;
+
+    // pay the settle bounty to the caller
+    let settle_bounty = ctx.accounts.otc_state.settle_bounty;
+    if settle_bounty > 0 {
+        ctx.accounts.otc_state.settle_bounty = 0;
+        **ctx.accounts.otc_state.to_account_info().try_borrow_mut_lamports()? -= settle_bounty;
+        **ctx.accounts.signer.to_account_info().try_borrow_mut_lamports()? += settle_bounty;
+    }
+
+    emit!(SettleEvent {
+        otc_state: ctx.accounts.otc_state.key(),
+        senior_tranche_amount,
+        junior_tranche_amount,
+        senior_redeemed_amount: ctx.accounts.otc_state.senior_redeemed_amount,
+        junior_redeemed_amount: ctx.accounts.otc_state.junior_redeemed_amount,
+        senior_reserve_amount: ctx.accounts.otc_senior_reserve_token_account.amount,
+        junior_reserve_amount: ctx.accounts.otc_junior_reserve_token_account.amount,
+        signer: ctx.accounts.signer.key(),
+        settle_bounty,
+        settle_timestamp: clock.unix_timestamp,
+        reserve_fair_value: ctx.accounts.otc_state.settle_reserve_fair_value,
+        tranche_fair_value: ctx.accounts.otc_state.settle_tranche_fair_value,
+    });
 
     Ok(())
 }
+
+#[event]
+pub struct SettleEvent {
+    pub otc_state: Pubkey,
+    pub senior_tranche_amount: u64,
+    pub junior_tranche_amount: u64,
+    pub senior_redeemed_amount: u64,
+    pub junior_redeemed_amount: u64,
+    pub senior_reserve_amount: u64,
+    pub junior_reserve_amount: u64,
+    pub signer: Pubkey,
+    pub settle_bounty: u64,
+    pub settle_timestamp: i64,
+    pub reserve_fair_value: [[u8; 16]; 10],
+    pub tranche_fair_value: [[u8; 16]; 2],
+}
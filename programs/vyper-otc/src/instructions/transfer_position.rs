@@ -0,0 +1,122 @@
+use crate::{
+    errors::{ VyperOtcErrorCode },
+    state::{ OtcState, OtcStatus }
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::{Mint, Token, TokenAccount, Transfer, self}};
+
+#[derive(Accounts)]
+pub struct TransferPositionContext<'info> {
+
+    /// Vault Configuration initialized
+    #[account(mut)]
+    pub otc_state: Box<Account<'info, OtcState>>,
+
+    /// Current beneficiary token account recorded for the side, its owner may not hold the position token anymore
+    #[account()]
+    pub beneficiary_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// New beneficiary token account
+    #[account(
+        constraint = new_beneficiary_token_account.mint == beneficiary_token_account.mint,
+        constraint = new_beneficiary_token_account.owner == new_owner.key())]
+    pub new_beneficiary_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: owner of the new beneficiary token account, receives the position token
+    #[account()]
+    pub new_owner: AccountInfo<'info>,
+
+    // - - - - - - - - - - - -
+    // Position Token
+
+    /// Position mint of the side to transfer
+    #[account()]
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    /// Signer position token account, the position token holder authorizes the transfer
+    #[account(mut, token::mint = position_mint, token::authority = signer)]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// New owner position token account, receives the position token
+    #[account(init_if_needed, payer = signer, associated_token::mint = position_mint, associated_token::authority = new_owner)]
+    pub new_position_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Rent program
+    pub rent: Sysvar<'info, Rent>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Signer account
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<TransferPositionContext>) -> Result<()> {
+
+    // positions can be transferred until the otc is settled
+    require!(
+        ctx.accounts.otc_state.status == OtcStatus::Open || ctx.accounts.otc_state.status == OtcStatus::Matched,
+        VyperOtcErrorCode::InvalidOtcStatus
+    );
+
+    // the side is selected by the position mint presented
+    let is_senior = ctx.accounts.otc_state
+        .position_side(&ctx.accounts.position_mint.key())
+        .ok_or(VyperOtcErrorCode::PositionMintNotFound)?;
+    let side_beneficiary = if is_senior {
+        ctx.accounts.otc_state.senior_side_beneficiary
+    } else {
+        ctx.accounts.otc_state.junior_side_beneficiary
+    };
+    if side_beneficiary != Some(ctx.accounts.beneficiary_token_account.key()) {
+        return err!(VyperOtcErrorCode::BeneficiaryNotFound);
+    }
+
+    // the position token holder novates the side, as it's the one able to claim it,
+    // afterwards the new owner both holds the position token and owns the recorded beneficiary
+    require_gte!(ctx.accounts.position_token_account.amount, 1, VyperOtcErrorCode::PositionTokenNotHeld);
+
+    // move the position token to the new owner
+    token::transfer(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.position_token_account.to_account_info(),
+            to: ctx.accounts.new_position_token_account.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+        },
+    ), 1)?;
+
+    // replace the beneficiary
+    let new_beneficiary = ctx.accounts.new_beneficiary_token_account.key();
+    if is_senior {
+        ctx.accounts.otc_state.senior_side_beneficiary = Some(new_beneficiary);
+    } else {
+        ctx.accounts.otc_state.junior_side_beneficiary = Some(new_beneficiary);
+    }
+
+    emit!(PositionTransferEvent {
+        otc_state: ctx.accounts.otc_state.key(),
+        is_senior_side: is_senior,
+        old_beneficiary: ctx.accounts.beneficiary_token_account.key(),
+        new_beneficiary,
+        new_position_token_account: ctx.accounts.new_position_token_account.key(),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PositionTransferEvent {
+    pub otc_state: Pubkey,
+    pub is_senior_side: bool,
+    pub old_beneficiary: Pubkey,
+    pub new_beneficiary: Pubkey,
+    pub new_position_token_account: Pubkey,
+}
@@ -0,0 +1,278 @@
+use crate::{
+    errors::{ VyperOtcErrorCode },
+    state::{ OtcState, OtcStatus }
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, Transfer, self};
+use vyper_core::{instructions::UpdateTrancheConfigFlags, state::{TrancheConfig}, program::VyperCore};
+
+#[derive(Accounts)]
+pub struct UnwindContext<'info> {
+    
+    /// Signer account, anyone can unwind
+    pub signer: Signer<'info>,
+
+    /// Vault Configuration initialized
+    #[account(mut,
+        has_one = otc_senior_reserve_token_account,
+        has_one = otc_junior_reserve_token_account,
+        has_one = otc_senior_tranche_token_account,
+        has_one = otc_junior_tranche_token_account,
+        has_one = otc_authority,
+        has_one = vyper_core,
+        has_one = vyper_tranche_config)]
+    pub otc_state: Box<Account<'info, OtcState>>,
+
+    /// CHECK: Vault Configuration Authority
+    #[account(seeds = [otc_state.key().as_ref(), b"authority".as_ref()], bump)]
+    pub otc_authority: AccountInfo<'info>,
+
+    // - - - - - - - - - - - - 
+    // OTC Token Accounts
+
+    /// OTC senior reserve token account
+    #[account(mut, token::mint = reserve_mint, token::authority = otc_authority)]
+    pub otc_senior_reserve_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// OTC junior reserve token account
+    #[account(mut, token::mint = reserve_mint, token::authority = otc_authority)]
+    pub otc_junior_reserve_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// OTC senior tranche token account
+    #[account(mut, token::mint = senior_tranche_mint, token::authority = otc_authority)]
+    pub otc_senior_tranche_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// OTC junior tranche token account
+    #[account(mut, token::mint = junior_tranche_mint, token::authority = otc_authority)]
+    pub otc_junior_tranche_token_account: Box<Account<'info, TokenAccount>>,
+
+    // - - - - - - - - - - - - 
+    // Token Mint
+
+    /// Reserve Token mint
+    #[account()]
+    pub reserve_mint: Box<Account<'info, Mint>>,
+
+    /// Senior Tranche Token mint
+    #[account(mut)]
+    pub senior_tranche_mint: Box<Account<'info, Mint>>,
+
+    /// Junior Tranche Token mint
+    #[account(mut)]
+    pub junior_tranche_mint: Box<Account<'info, Mint>>,
+
+    // - - - - - - - - - - - - 
+    // Vyper Accounts
+
+    /// Vyper Core Tranche Configuration
+    #[account(mut, has_one = reserve_mint, has_one = senior_tranche_mint, has_one = junior_tranche_mint)]
+    pub vyper_tranche_config: Box<Account<'info, TrancheConfig>>,
+
+    /// Vyper Core tranche configuration authority
+    /// CHECK:
+    #[account()]
+    pub vyper_tranche_authority: AccountInfo<'info>,
+
+    /// Vyper Core reserve token account
+    #[account(mut)]
+    pub vyper_reserve: Box<Account<'info, TokenAccount>>,
+
+    /// Vyper Core program
+    pub vyper_core: Program<'info, VyperCore>,
+
+    /// Rent program
+    pub rent: Sysvar<'info, Rent>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> UnwindContext<'info> {
+
+    fn to_vyper_update_context(
+        &self
+    ) -> CpiContext<'_, '_, '_, 'info, vyper_core::cpi::accounts::UpdateTrancheDataContext<'info>> {
+        CpiContext::new(
+            self.vyper_core.to_account_info(),
+            vyper_core::cpi::accounts::UpdateTrancheDataContext {
+                owner: self.otc_authority.to_account_info(),
+                tranche_config: self.vyper_tranche_config.to_account_info(),
+            },
+        )
+    }
+    
+    fn to_vyper_redeem_context(
+        &self, is_senior: bool
+    ) -> CpiContext<'_, '_, '_, 'info, vyper_core::cpi::accounts::RedeemContext<'info>> {
+
+        let dest_reserve_account = if is_senior {
+            &self.otc_senior_reserve_token_account
+        } else {
+            &self.otc_junior_reserve_token_account
+        };
+
+        CpiContext::new(
+            self.vyper_core.to_account_info(),
+            vyper_core::cpi::accounts::RedeemContext {
+                signer: self.otc_authority.to_account_info(),
+                tranche_config: self.vyper_tranche_config.to_account_info(),
+                tranche_authority: self.vyper_tranche_authority.to_account_info(),
+                reserve: self.vyper_reserve.to_account_info(),
+                user_reserve_token: dest_reserve_account.to_account_info(),
+                senior_tranche_mint: self.senior_tranche_mint.to_account_info(),
+                junior_tranche_mint: self.junior_tranche_mint.to_account_info(),
+                senior_tranche_source: self.otc_senior_tranche_token_account.to_account_info(),
+                junior_tranche_source: self.otc_junior_tranche_token_account.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                rent: self.rent.to_account_info(),
+            },
+        )
+    }
+}
+
+pub fn handler(ctx: Context<UnwindContext>) -> Result<()> {
+
+    // unwind is a fallback for settlements not executed by the deadline,
+    // it's available while paused as it only returns funds
+    let clock = Clock::get()?;
+    if !ctx.accounts.otc_state.is_settle_expired(clock.unix_timestamp) {
+        return err!(VyperOtcErrorCode::SettleDeadlineNotReached);
+    }
+    if let OtcStatus::Settled | OtcStatus::Claimed = ctx.accounts.otc_state.status {
+        return err!(VyperOtcErrorCode::SettleAlreadyExecuted);
+    }
+    require!(ctx.accounts.otc_state.status == OtcStatus::Matched, VyperOtcErrorCode::InvalidOtcStatus);
+
+    // the otc authority owns the tranche configuration:
+    // lift the halt flags and accept the last tranche fair value however stale it is
+    vyper_core::cpi::update_tranche_data(
+        ctx.accounts
+            .to_vyper_update_context()
+            .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
+        vyper_core::instructions::UpdateTrancheDataInput {
+            bitmask: (UpdateTrancheConfigFlags::HALT_FLAGS | UpdateTrancheConfigFlags::TRANCHE_FAIR_VALUE_STALE_SLOT_THRESHOLD).bits(),
+            halt_flags: 0,
+            owner_restricted_ixs: 0,
+            reserve_fair_value_stale_slot_threshold: 0,
+            tranche_fair_value_stale_slot_threshold: u64::MAX,
+        },
+    )?;
+
+    let senior_tranche_amount = ctx.accounts.otc_senior_tranche_token_account.amount;
+    let junior_tranche_amount = ctx.accounts.otc_junior_tranche_token_account.amount;
+    let senior_reserve_amount_before = ctx.accounts.otc_senior_reserve_token_account.amount;
+    let junior_reserve_amount_before = ctx.accounts.otc_junior_reserve_token_account.amount;
+
+    // redeem assets
+    vyper_core::cpi::redeem(
+        ctx.accounts
+            .to_vyper_redeem_context(true)
+            .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
+        vyper_core::instructions::RedeemInput {
+            tranche_quantity: [senior_tranche_amount, 0],
+        },
+    )?;
+    vyper_core::cpi::redeem(
+        ctx.accounts
+            .to_vyper_redeem_context(false)
+            .with_signer(&[&ctx.accounts.otc_state.authority_seeds()]),
+        vyper_core::instructions::RedeemInput {
+            tranche_quantity: [0, junior_tranche_amount],
+        },
+    )?;
+
+    ctx.accounts.otc_senior_reserve_token_account.reload()?;
+    ctx.accounts.otc_junior_reserve_token_account.reload()?;
+
+    // the last fair value cannot be trusted after the deadline:
+    // the total redeemed is split in proportion to the amounts deposited on vyper
+    let senior_received_amount = ctx.accounts.otc_senior_reserve_token_account.amount
+        .checked_sub(senior_reserve_amount_before)
+        .ok_or(VyperOtcErrorCode::MathError)?;
+    let junior_received_amount = ctx.accounts.otc_junior_reserve_token_account.amount
+        .checked_sub(junior_reserve_amount_before)
+        .ok_or(VyperOtcErrorCode::MathError)?;
+    let total_redeemed_amount = senior_received_amount
+        .checked_add(junior_received_amount)
+        .ok_or(VyperOtcErrorCode::MathError)?;
+    let senior_net_deposit = ctx.accounts.otc_state.senior_deposit_amount - ctx.accounts.otc_state.fee_amount(ctx.accounts.otc_state.senior_deposit_amount)?;
+    let junior_net_deposit = ctx.accounts.otc_state.junior_deposit_amount - ctx.accounts.otc_state.fee_amount(ctx.accounts.otc_state.junior_deposit_amount)?;
+    let net_deposit = (senior_net_deposit as u128) + (junior_net_deposit as u128);
+    let senior_redeemed_amount = if net_deposit == 0 {
+        0
+    } else {
+        ((total_redeemed_amount as u128) * (senior_net_deposit as u128) / net_deposit) as u64
+    };
+    let junior_redeemed_amount = total_redeemed_amount - senior_redeemed_amount;
+
+    // move the difference between the side reserve accounts, claims pay from the side account
+    rebalance_side_reserves(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.otc_senior_reserve_token_account.to_account_info(),
+        ctx.accounts.otc_junior_reserve_token_account.to_account_info(),
+        ctx.accounts.otc_authority.to_account_info(),
+        &ctx.accounts.otc_state.authority_seeds(),
+        senior_received_amount,
+        senior_redeemed_amount,
+    )?;
+
+    ctx.accounts.otc_state.senior_redeemed_amount = senior_redeemed_amount;
+    ctx.accounts.otc_state.junior_redeemed_amount = junior_redeemed_amount;
+    ctx.accounts.otc_state.settle_timestamp = Some(clock.unix_timestamp);
+    ctx.accounts.otc_state.status = OtcStatus::Settled;
+
+    emit!(UnwindEvent {
+        otc_state: ctx.accounts.otc_state.key(),
+        signer: ctx.accounts.signer.key(),
+        settle_timestamp: clock.unix_timestamp,
+        senior_tranche_amount,
+        junior_tranche_amount,
+        total_redeemed_amount,
+        senior_redeemed_amount,
+        junior_redeemed_amount,
+    });
+
+    Ok(())
+}
+
+/// transfers between the side reserve accounts so that the senior side holds `senior_amount`
+/// out of the `senior_received_amount` it received from vyper, the junior side holding the rest
+pub fn rebalance_side_reserves<'info>(
+    token_program: AccountInfo<'info>,
+    senior_reserve: AccountInfo<'info>,
+    junior_reserve: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    authority_seeds: &[&[u8]],
+    senior_received_amount: u64,
+    senior_amount: u64,
+) -> Result<()> {
+    if senior_received_amount == senior_amount {
+        return Ok(());
+    }
+    let (from, to, amount) = if senior_received_amount > senior_amount {
+        (senior_reserve, junior_reserve, senior_received_amount - senior_amount)
+    } else {
+        (junior_reserve, senior_reserve, senior_amount - senior_received_amount)
+    };
+    token::transfer(CpiContext::new_with_signer(
+        token_program,
+        Transfer { from, to, authority },
+        &[authority_seeds]
+    ), amount)
+}
+
+#[event]
+pub struct UnwindEvent {
+    pub otc_state: Pubkey,
+    pub signer: Pubkey,
+    pub settle_timestamp: i64,
+    pub senior_tranche_amount: u64,
+    pub junior_tranche_amount: u64,
+    pub total_redeemed_amount: u64,
+    pub senior_redeemed_amount: u64,
+    pub junior_redeemed_amount: u64,
+}
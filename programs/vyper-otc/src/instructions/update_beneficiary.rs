@@ -0,0 +1,90 @@
+use crate::{
+    errors::{ VyperOtcErrorCode },
+    state::{ OtcState, OtcStatus }
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct UpdateBeneficiaryContext<'info> {
+
+    /// Vault Configuration initialized
+    #[account(mut, has_one = otc_senior_reserve_token_account)]
+    pub otc_state: Box<Account<'info, OtcState>>,
+
+    /// New beneficiary token account
+    #[account(constraint = new_beneficiary_token_account.mint == otc_senior_reserve_token_account.mint)]
+    pub new_beneficiary_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// OTC senior reserve token account
+    #[account()]
+    pub otc_senior_reserve_token_account: Box<Account<'info, TokenAccount>>,
+
+    // - - - - - - - - - - - -
+    // Position Token
+
+    /// Position mint of the side to update
+    #[account()]
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    /// Signer position token account
+    #[account(token::mint = position_mint, token::authority = signer)]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Signer account
+    pub signer: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdateBeneficiaryContext>) -> Result<()> {
+
+    // the beneficiary can be updated until the side is paid out
+    require!(
+        ctx.accounts.otc_state.status == OtcStatus::Open
+            || ctx.accounts.otc_state.status == OtcStatus::Matched
+            || ctx.accounts.otc_state.status == OtcStatus::Settled,
+        VyperOtcErrorCode::InvalidOtcStatus
+    );
+
+    // the side is selected by the position mint presented,
+    // the current beneficiary token account is not required as it may be lost
+    let is_senior = ctx.accounts.otc_state
+        .position_side(&ctx.accounts.position_mint.key())
+        .ok_or(VyperOtcErrorCode::PositionMintNotFound)?;
+    let (side_beneficiary, claimed_amount) = if is_senior {
+        (ctx.accounts.otc_state.senior_side_beneficiary, ctx.accounts.otc_state.senior_claimed_amount)
+    } else {
+        (ctx.accounts.otc_state.junior_side_beneficiary, ctx.accounts.otc_state.junior_claimed_amount)
+    };
+    let old_beneficiary = side_beneficiary.ok_or(VyperOtcErrorCode::BeneficiaryNotFound)?;
+    if claimed_amount.is_some() {
+        return err!(VyperOtcErrorCode::SideAlreadyClaimed);
+    }
+    require_gte!(ctx.accounts.position_token_account.amount, 1, VyperOtcErrorCode::PositionTokenNotHeld);
+
+    // replace the beneficiary
+    let new_beneficiary = ctx.accounts.new_beneficiary_token_account.key();
+    if is_senior {
+        ctx.accounts.otc_state.senior_side_beneficiary = Some(new_beneficiary);
+    } else {
+        ctx.accounts.otc_state.junior_side_beneficiary = Some(new_beneficiary);
+    }
+
+    emit!(BeneficiaryUpdateEvent {
+        otc_state: ctx.accounts.otc_state.key(),
+        is_senior_side: is_senior,
+        old_beneficiary,
+        new_beneficiary,
+        position_holder: ctx.accounts.signer.key(),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct BeneficiaryUpdateEvent {
+    pub otc_state: Pubkey,
+    pub is_senior_side: bool,
+    pub old_beneficiary: Pubkey,
+    pub new_beneficiary: Pubkey,
+    pub position_holder: Pubkey,
+}
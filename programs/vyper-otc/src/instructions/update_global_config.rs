@@ -0,0 +1,65 @@
+use crate::{
+    instructions::GlobalConfigInputData,
+    state::{ GlobalConfig }
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateGlobalConfigContext<'info> {
+
+    /// Global Configuration
+    #[account(mut, seeds = [GlobalConfig::SEED], bump = global_config.bump, has_one = admin)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// Current admin, a new one provided in the input data needs to accept the rotation
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdateGlobalConfigContext>, input_data: GlobalConfigInputData) -> Result<()> {
+
+    input_data.validate()?;
+
+    let global_config = &mut ctx.accounts.global_config;
+    // a mistyped admin can't lock the configuration, the current one stays until the new one accepts
+    global_config.pending_admin = if input_data.admin == global_config.admin {
+        None
+    } else {
+        Some(input_data.admin)
+    };
+    global_config.fee_bps = input_data.fee_bps;
+    global_config.treasury = input_data.treasury;
+    global_config.min_duration = input_data.min_duration;
+    global_config.max_duration = input_data.max_duration;
+    global_config.settle_grace_period = input_data.settle_grace_period;
+    global_config.allowed_rate_programs = input_data.allowed_rate_programs.clone();
+    global_config.allowed_redeem_logic_programs = input_data.allowed_redeem_logic_programs.clone();
+
+    emit!(GlobalConfigUpdateEvent {
+        global_config: ctx.accounts.global_config.key(),
+        admin: ctx.accounts.global_config.admin,
+        pending_admin: ctx.accounts.global_config.pending_admin,
+        fee_bps: input_data.fee_bps,
+        treasury: input_data.treasury,
+        min_duration: input_data.min_duration,
+        max_duration: input_data.max_duration,
+        settle_grace_period: input_data.settle_grace_period,
+        allowed_rate_programs: input_data.allowed_rate_programs,
+        allowed_redeem_logic_programs: input_data.allowed_redeem_logic_programs,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct GlobalConfigUpdateEvent {
+    pub global_config: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+    pub min_duration: Option<i64>,
+    pub max_duration: Option<i64>,
+    pub settle_grace_period: i64,
+    pub allowed_rate_programs: Vec<Pubkey>,
+    pub allowed_redeem_logic_programs: Vec<Pubkey>,
+}
@@ -1,9 +1,9 @@
 use crate::{
     errors::{ VyperOtcErrorCode },
-    state::{ OtcState }
+    state::{ OtcState, OtcStatus }
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount, Transfer, self};
+use anchor_spl::token::{Burn, CloseAccount, Mint, Token, TokenAccount, Transfer, self};
 
 #[derive(Accounts)]
 pub struct WithdrawContext<'info> {
@@ -12,7 +12,7 @@ pub struct WithdrawContext<'info> {
     #[account(mut, token::authority = signer)]
     pub user_reserve_token_account: Box<Account<'info, TokenAccount>>,
 
-    /// User reserve token account
+    /// Token account receiving the refund
     #[account(mut, token::mint = reserve_mint)]
     pub beneficiary_token_account: Box<Account<'info, TokenAccount>>,
 
@@ -38,6 +38,17 @@ pub struct WithdrawContext<'info> {
     #[account(mut, token::mint = reserve_mint, token::authority = otc_authority)]
     pub otc_junior_reserve_token_account: Box<Account<'info, TokenAccount>>,
     
+    // - - - - - - - - - - - - 
+    // Position Token
+
+    /// Position mint of the side to withdraw
+    #[account(mut)]
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    /// Signer position token account, the position token is burned
+    #[account(mut, token::mint = position_mint, token::authority = signer)]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
     // - - - - - - - - - - - - 
     // Token Mint
 
@@ -59,61 +70,118 @@ pub struct WithdrawContext<'info> {
     pub signer: Signer<'info>,
 }
 
-pub fn handler(ctx: Context<WithdrawContext>) -> Result<()> {
-    
-    // check that the deposits are closed and one side is not taken
-    let clock = Clock::get()?;
-    if clock.unix_timestamp < ctx.accounts.otc_state.deposit_end {
-        return err!(VyperOtcErrorCode::DepositOpen);
-    }
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug)]
+pub struct WithdrawInputData {
+    /// close the beneficiary token account to the signer, the reserve mint needs to be the native mint
+    pub unwrap_native: bool,
+}
 
-    // withdraw is not possible is we have both sides taken
-    if ctx.accounts.otc_state.senior_side_beneficiary.is_some() && ctx.accounts.otc_state.junior_side_beneficiary.is_some() {
+pub fn handler(ctx: Context<WithdrawContext>, input_data: WithdrawInputData) -> Result<()> {
+    
+    // withdraw is possible at any time, also while deposits are open,
+    // but not if we have both sides taken
+    if ctx.accounts.otc_state.both_sides_taken() {
         return err!(VyperOtcErrorCode::BothPositionsTaken);
     }
-
-    let mut is_senior_opt: Option<bool> = None;
-
-    if let Some(senior_side_beneficiary) = ctx.accounts.otc_state.senior_side_beneficiary {
-        if senior_side_beneficiary == ctx.accounts.beneficiary_token_account.key() {
-            is_senior_opt = Some(true);
-        }
-    } 
-
-    if let Some(junior_side_beneficiary) = ctx.accounts.otc_state.junior_side_beneficiary {
-        if junior_side_beneficiary == ctx.accounts.beneficiary_token_account.key() {
-            is_senior_opt = Some(false);
-        }
+    if ctx.accounts.otc_state.status == OtcStatus::Refunded {
+        return err!(VyperOtcErrorCode::SideAlreadyWithdrawn);
+    }
+    require!(ctx.accounts.otc_state.status == OtcStatus::Open, VyperOtcErrorCode::InvalidOtcStatus);
+
+    // the side is selected by the position mint presented
+    let is_senior = ctx.accounts.otc_state
+        .position_side(&ctx.accounts.position_mint.key())
+        .ok_or(VyperOtcErrorCode::PositionMintNotFound)?;
+    let side_beneficiary = if is_senior {
+        ctx.accounts.otc_state.senior_side_beneficiary
+    } else {
+        ctx.accounts.otc_state.junior_side_beneficiary
+    };
+    if side_beneficiary.is_none() {
+        return err!(VyperOtcErrorCode::BeneficiaryNotFound);
+    }
+    require_gte!(ctx.accounts.position_token_account.amount, 1, VyperOtcErrorCode::PositionTokenNotHeld);
+
+    // burn the position token
+    token::burn(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.position_mint.to_account_info(),
+            to: ctx.accounts.position_token_account.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+        },
+    ), 1)?;
+
+    // only the deposited amount is refunded
+    let (amount, source_account) = if is_senior {
+        (ctx.accounts.otc_state.senior_deposit_amount, &ctx.accounts.otc_senior_reserve_token_account)
+    } else {
+        (ctx.accounts.otc_state.junior_deposit_amount, &ctx.accounts.otc_junior_reserve_token_account)
+    };
+
+    // transfer assets
+    token::transfer(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: source_account.to_account_info(),
+            to: ctx.accounts.beneficiary_token_account.to_account_info(),
+            authority: ctx.accounts.otc_authority.to_account_info(),
+        },
+        &[&ctx.accounts.otc_state.authority_seeds()]
+    ), amount)?;
+
+    // unwrap to the signer, who needs to own the beneficiary token account
+    if input_data.unwrap_native {
+        require_keys_eq!(ctx.accounts.reserve_mint.key(), token::spl_token::native_mint::ID, VyperOtcErrorCode::NativeMintRequired);
+        token::close_account(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.beneficiary_token_account.to_account_info(),
+                destination: ctx.accounts.signer.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ))?;
     }
 
-    if let Some(is_senior) = is_senior_opt {
-
-        // transfer assets
-        if is_senior {
-            token::transfer(CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.otc_senior_reserve_token_account.to_account_info(),
-                    to: ctx.accounts.beneficiary_token_account.to_account_info(),
-                    authority: ctx.accounts.otc_authority.to_account_info(),
-                },
-                &[&ctx.accounts.otc_state.authority_seeds()]
-            ), ctx.accounts.otc_senior_reserve_token_account.amount)?;
-        } else {
-            token::transfer(CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.otc_junior_reserve_token_account.to_account_info(),
-                    to: ctx.accounts.beneficiary_token_account.to_account_info(),
-                    authority: ctx.accounts.otc_authority.to_account_info(),
-                },
-                &[&ctx.accounts.otc_state.authority_seeds()]
-            ), ctx.accounts.otc_junior_reserve_token_account.amount)?;
-        }
+    // release the side so that a new counterparty can take it
+    let otc_state = &mut ctx.accounts.otc_state;
+    if is_senior {
+        otc_state.senior_side_beneficiary = None;
+        otc_state.senior_withdrawn_amount = otc_state.senior_withdrawn_amount.checked_add(amount).ok_or(VyperOtcErrorCode::MathError)?;
+    } else {
+        otc_state.junior_side_beneficiary = None;
+        otc_state.junior_withdrawn_amount = otc_state.junior_withdrawn_amount.checked_add(amount).ok_or(VyperOtcErrorCode::MathError)?;
     }
-    else {
-        return err!(VyperOtcErrorCode::BeneficiaryNotFound);
+
+    // once deposits are expired the side can't be taken again
+    let clock = Clock::get()?;
+    if ctx.accounts.otc_state.is_deposit_expired(clock.unix_timestamp) {
+        ctx.accounts.otc_state.status = OtcStatus::Refunded;
     }
 
+    ctx.accounts.otc_senior_reserve_token_account.reload()?;
+    ctx.accounts.otc_junior_reserve_token_account.reload()?;
+
+    emit!(WithdrawEvent {
+        otc_state: ctx.accounts.otc_state.key(),
+        is_senior_side: is_senior,
+        beneficiary: ctx.accounts.beneficiary_token_account.key(),
+        position_token_account: ctx.accounts.position_token_account.key(),
+        amount,
+        senior_reserve_amount: ctx.accounts.otc_senior_reserve_token_account.amount,
+        junior_reserve_amount: ctx.accounts.otc_junior_reserve_token_account.amount,
+    });
+
     Ok(())
 }
+
+#[event]
+pub struct WithdrawEvent {
+    pub otc_state: Pubkey,
+    pub is_senior_side: bool,
+    pub beneficiary: Pubkey,
+    pub position_token_account: Pubkey,
+    pub amount: u64,
+    pub senior_reserve_amount: u64,
+    pub junior_reserve_amount: u64,
+}
@@ -30,8 +30,9 @@ pub mod vyper_otc {
     #[access_control(pre_ix("withdraw"))]
     pub fn withdraw(
         ctx: Context<WithdrawContext>,
+        input_data: WithdrawInputData,
     ) -> Result<()> {
-        instructions::withdraw::handler(ctx)
+        instructions::withdraw::handler(ctx, input_data)
     }
 
     #[access_control(pre_ix("settle"))]
@@ -44,8 +45,9 @@ pub mod vyper_otc {
     #[access_control(pre_ix("claim"))]
     pub fn claim(
         ctx: Context<ClaimContext>,
+        input_data: ClaimInputData,
     ) -> Result<()> {
-        instructions::claim::handler(ctx)
+        instructions::claim::handler(ctx, input_data)
     }
 
     #[access_control(pre_ix("close"))]
@@ -54,6 +56,81 @@ pub mod vyper_otc {
     ) -> Result<()> {
         instructions::close::handler(ctx)
     }
+
+    #[access_control(pre_ix("cancel"))]
+    pub fn cancel(
+        ctx: Context<CancelContext>,
+    ) -> Result<()> {
+        instructions::cancel::handler(ctx)
+    }
+
+    #[access_control(pre_ix("initialize_global_config"))]
+    pub fn initialize_global_config(
+        ctx: Context<InitializeGlobalConfigContext>,
+        input_data: GlobalConfigInputData,
+    ) -> Result<()> {
+        instructions::initialize_global_config::handler(ctx, input_data)
+    }
+
+    #[access_control(pre_ix("update_global_config"))]
+    pub fn update_global_config(
+        ctx: Context<UpdateGlobalConfigContext>,
+        input_data: GlobalConfigInputData,
+    ) -> Result<()> {
+        instructions::update_global_config::handler(ctx, input_data)
+    }
+
+    #[access_control(pre_ix("set_paused"))]
+    pub fn set_paused(
+        ctx: Context<SetPausedContext>,
+        paused: bool,
+    ) -> Result<()> {
+        instructions::set_paused::handler(ctx, paused)
+    }
+
+    #[access_control(pre_ix("accept_admin"))]
+    pub fn accept_admin(
+        ctx: Context<AcceptAdminContext>,
+    ) -> Result<()> {
+        instructions::accept_admin::handler(ctx)
+    }
+
+    #[access_control(pre_ix("transfer_position"))]
+    pub fn transfer_position(
+        ctx: Context<TransferPositionContext>,
+    ) -> Result<()> {
+        instructions::transfer_position::handler(ctx)
+    }
+
+    #[access_control(pre_ix("update_beneficiary"))]
+    pub fn update_beneficiary(
+        ctx: Context<UpdateBeneficiaryContext>,
+    ) -> Result<()> {
+        instructions::update_beneficiary::handler(ctx)
+    }
+
+    #[access_control(pre_ix("distribute"))]
+    pub fn distribute(
+        ctx: Context<DistributeContext>,
+        input_data: DistributeInputData,
+    ) -> Result<()> {
+        instructions::distribute::handler(ctx, input_data)
+    }
+
+    #[access_control(pre_ix("unwind"))]
+    pub fn unwind(
+        ctx: Context<UnwindContext>,
+    ) -> Result<()> {
+        instructions::unwind::handler(ctx)
+    }
+
+    #[access_control(pre_ix("early_terminate"))]
+    pub fn early_terminate(
+        ctx: Context<EarlyTerminateContext>,
+        input_data: EarlyTerminateInputData,
+    ) -> Result<()> {
+        instructions::early_terminate::handler(ctx, input_data)
+    }
 }
 
 
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct GlobalConfig {
+
+    pub admin: Pubkey,
+
+    /// admin proposed by the current one, it takes over once it accepts
+    pub pending_admin: Option<Pubkey>,
+
+    /// protocol fee charged on each side when both sides are matched, in basis points
+    pub fee_bps: u16,
+
+    /// owner of the token accounts receiving the protocol fees
+    pub treasury: Pubkey,
+
+    /// bounds on the otc duration, from deposit start to settle start
+    pub min_duration: Option<i64>,
+    pub max_duration: Option<i64>,
+
+    /// delay from settle start to the settle deadline of otcs created without one
+    pub settle_grace_period: i64,
+
+    /// rate plugin programs allowed on vyper tranche configurations, any if empty
+    pub allowed_rate_programs: Vec<Pubkey>,
+
+    /// redeem logic programs allowed on vyper tranche configurations, any if empty
+    pub allowed_redeem_logic_programs: Vec<Pubkey>,
+
+    /// blocks new otcs, deposits and settlements, funds can always be withdrawn, claimed or closed
+    pub paused: bool,
+
+    pub bump: u8,
+
+    pub version: [u8; 3],
+}
+
+impl GlobalConfig {
+    pub const SEED: &'static [u8] = b"global_config";
+
+    pub const MAX_FEE_BPS: u16 = 10_000;
+
+    pub const MAX_ALLOWED_PROGRAMS: usize = 10;
+
+    pub fn is_duration_allowed(&self, duration: i64) -> bool {
+        self.min_duration.map_or(true, |min_duration| duration >= min_duration)
+            && self.max_duration.map_or(true, |max_duration| duration <= max_duration)
+    }
+
+    pub fn is_rate_program_allowed(&self, rate_program: &Pubkey) -> bool {
+        self.allowed_rate_programs.is_empty() || self.allowed_rate_programs.contains(rate_program)
+    }
+
+    pub fn is_redeem_logic_program_allowed(&self, redeem_logic_program: &Pubkey) -> bool {
+        self.allowed_redeem_logic_programs.is_empty() || self.allowed_redeem_logic_programs.contains(redeem_logic_program)
+    }
+
+    pub const LEN: usize = 8 + // discriminator
+    32 + // pub admin: Pubkey,
+    1+32 + // pub pending_admin: Option<Pubkey>,
+    2 + // pub fee_bps: u16,
+    32 + // pub treasury: Pubkey,
+    1+8 + // pub min_duration: Option<i64>,
+    1+8 + // pub max_duration: Option<i64>,
+    8 + // pub settle_grace_period: i64,
+    4+32*GlobalConfig::MAX_ALLOWED_PROGRAMS + // pub allowed_rate_programs: Vec<Pubkey>,
+    4+32*GlobalConfig::MAX_ALLOWED_PROGRAMS + // pub allowed_redeem_logic_programs: Vec<Pubkey>,
+    1 + // pub paused: bool,
+    1 + // pub bump: u8,
+    3 // pub version: [u8; 3],
+    ;
+}
@@ -1,3 +1,7 @@
 
+pub mod global_config;
 pub mod otc_state;
-pub use otc_state::*;
\ No newline at end of file
+pub mod otc_status;
+pub use global_config::*;
+pub use otc_state::*;
+pub use otc_status::*;
\ No newline at end of file
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 
+use super::{ GlobalConfig, OtcStatus };
+use crate::errors::VyperOtcErrorCode;
+
 #[account]
 pub struct OtcState {
 
@@ -7,22 +10,53 @@ pub struct OtcState {
     pub deposit_start: i64,
     pub deposit_end: i64,
     pub settle_start: i64,
-    pub settle_executed: bool,
+
+    /// past this timestamp a matched otc can be unwound without the vyper fair value
+    pub settle_deadline: i64,
+
+    pub status: OtcStatus,
     
     pub senior_deposit_amount: u64,
     pub junior_deposit_amount: u64,
+    pub fee_bps: u16,
+
+    /// lamports held by the otc state on top of its rent, paid to the first settle caller
+    /// or refunded to the creator when the otc state is closed
+    pub settle_bounty: u64,
 
     pub senior_side_beneficiary: Option<Pubkey>,
     pub junior_side_beneficiary: Option<Pubkey>,
 
+    pub senior_counterparty: Option<Pubkey>,
+    pub junior_counterparty: Option<Pubkey>,
+
+    pub senior_withdrawn_amount: u64,
+    pub junior_withdrawn_amount: u64,
+    pub senior_redeemed_amount: u64,
+    pub junior_redeemed_amount: u64,
+    pub senior_claimed_amount: Option<u64>,
+    pub junior_claimed_amount: Option<u64>,
+
+    /// settlement time and the vyper-core fair values used to redeem,
+    /// as serialized `rust_decimal::Decimal`, left empty when the split is not the vyper one
+    pub settle_timestamp: Option<i64>,
+    pub settle_reserve_fair_value: [[u8; 16]; 10],
+    pub settle_tranche_fair_value: [[u8; 16]; 2],
+
     pub vyper_tranche_config: Pubkey,
     pub vyper_core: Pubkey,
 
+    pub creator: Pubkey,
+
     pub otc_senior_reserve_token_account: Pubkey,
     pub otc_junior_reserve_token_account: Pubkey,
     pub otc_senior_tranche_token_account: Pubkey,
     pub otc_junior_tranche_token_account: Pubkey,
 
+    /// mints of the 1-of-1 position tokens, the holder can withdraw or claim the side
+    pub senior_position_mint: Pubkey,
+    pub junior_position_mint: Pubkey,
+
     pub otc_authority: Pubkey,
     pub authority_seed: Pubkey,
     pub authority_bump: [u8; 1],
@@ -39,22 +73,82 @@ impl OtcState {
         ]
     }
 
+    /// deposits are accepted from `deposit_start` to `deposit_end`, both included
+    pub fn is_deposit_started(&self, now: i64) -> bool {
+        now >= self.deposit_start
+    }
+
+    pub fn is_deposit_expired(&self, now: i64) -> bool {
+        now > self.deposit_end
+    }
+
+    /// settlement is available from `settle_start` included
+    pub fn is_settle_available(&self, now: i64) -> bool {
+        now >= self.settle_start
+    }
+
+    /// unwind is available after `settle_deadline`, excluded
+    pub fn is_settle_expired(&self, now: i64) -> bool {
+        now > self.settle_deadline
+    }
+
+    pub fn both_sides_taken(&self) -> bool {
+        self.senior_side_beneficiary.is_some() && self.junior_side_beneficiary.is_some()
+    }
+
+    /// side of a position mint, `Some(true)` for the senior side
+    pub fn position_side(&self, position_mint: &Pubkey) -> Option<bool> {
+        if *position_mint == self.senior_position_mint {
+            Some(true)
+        } else if *position_mint == self.junior_position_mint {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// protocol fee charged on a side deposit when both sides are matched
+    pub fn fee_amount(&self, deposit_amount: u64) -> Result<u64> {
+        let fee = (deposit_amount as u128)
+            .checked_mul(self.fee_bps as u128)
+            .ok_or(VyperOtcErrorCode::MathError)?
+            / GlobalConfig::MAX_FEE_BPS as u128;
+        u64::try_from(fee).map_err(|_| error!(VyperOtcErrorCode::MathError))
+    }
+
     pub const LEN: usize = 8 + // discriminator
     8 + // pub created: i64,
     8 + // pub deposit_start: i64,
     8 + // pub deposit_end: i64,
     8 + // pub settle_start: i64,
-    1 + // pub settle_executed: bool,
+    8 + // pub settle_deadline: i64,
+    1 + // pub status: OtcStatus,
     8 + // pub senior_deposit_amount: u64,
     8 + // pub junior_deposit_amount: u64,
+    2 + // pub fee_bps: u16,
+    8 + // pub settle_bounty: u64,
     1+32 + // pub senior_side_beneficiary: Option<Pubkey>,
     1+32 + // pub junior_side_beneficiary: Option<Pubkey>,
+    1+32 + // pub senior_counterparty: Option<Pubkey>,
+    1+32 + // pub junior_counterparty: Option<Pubkey>,
+    8 + // pub senior_withdrawn_amount: u64,
+    8 + // pub junior_withdrawn_amount: u64,
+    8 + // pub senior_redeemed_amount: u64,
+    8 + // pub junior_redeemed_amount: u64,
+    1+8 + // pub senior_claimed_amount: Option<u64>,
+    1+8 + // pub junior_claimed_amount: Option<u64>,
+    1+8 + // pub settle_timestamp: Option<i64>,
+    16*10 + // pub settle_reserve_fair_value: [[u8; 16]; 10],
+    16*2 + // pub settle_tranche_fair_value: [[u8; 16]; 2],
     32 + // pub vyper_tranche_config: Pubkey,
     32 + // pub vyper_core: Pubkey,
+    32 + // pub creator: Pubkey,
     32 + // pub otc_senior_reserve_token_account: Pubkey,
     32 + // pub otc_junior_reserve_token_account: Pubkey,
     32 + // pub otc_senior_tranche_token_account: Pubkey,
     32 + // pub otc_junior_tranche_token_account: Pubkey,
+    32 + // pub senior_position_mint: Pubkey,
+    32 + // pub junior_position_mint: Pubkey,
     32 + // pub otc_authority: Pubkey,
     32 + // pub authority_seed: Pubkey,
     1 + // pub authority_bump: [u8; 1],
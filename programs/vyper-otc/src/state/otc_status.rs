@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+/// Lifecycle of an OTC contract.
+///
+/// Time boundaries, all inclusive on the left side:
+/// - deposits are accepted in `[deposit_start, deposit_end]`
+/// - deposits are expired from `deposit_end + 1`
+/// - settlement is available from `settle_start`
+/// - unwind is available from `settle_deadline + 1`
+///
+/// Transitions:
+/// - `initialize`: -> `Open`
+/// - `deposit`: `Open` -> `Open` with one side taken, `Open` -> `Matched` when both sides are taken
+/// - `transfer_position`: no transition, available in `Open` and `Matched`
+/// - `update_beneficiary`: no transition, available in `Open`, `Matched` and `Settled`
+/// - `withdraw`: `Open` -> `Open` while deposits are open, `Open` -> `Refunded` once they are expired
+/// - `settle`: `Matched` -> `Settled`
+/// - `early_terminate`: `Matched` -> `Settled` before `settle_start`, signed by both position holders,
+///   at the current fair value only while the program is not paused
+/// - `unwind`: `Matched` -> `Settled`, splitting the redeemed collateral in proportion to the deposits
+/// - `claim`: `Settled` -> `Settled` for the first side, `Settled` -> `Claimed` when both sides are paid out
+/// - `distribute`: same as `claim`, paying the recorded beneficiary without its signature
+/// - `cancel`: `Open` -> `Closed`
+/// - `close`: `Open` (expired, no side taken), `Refunded` or `Claimed` -> `Closed`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OtcStatus {
+    /// waiting for one or both sides to be taken
+    Open,
+
+    /// both sides taken, collateral deposited on vyper
+    Matched,
+
+    /// deposits expired with a single side taken, which has been withdrawn
+    Refunded,
+
+    /// tranches redeemed from vyper, sides can be claimed
+    Settled,
+
+    /// both sides have been claimed
+    Claimed,
+
+    /// contract closed, only observable in the same transaction
+    Closed,
+}
@@ -0,0 +1,540 @@
+//! In-process runtime with vyper-otc, vyper-core, the mock rate plugin
+//! and the vanilla option redeem logic plugin.
+//!
+//! The global configuration is stored at genesis, `initialize_global_config`
+//! needs an upgradeable program data account which builtin programs don't have.
+
+#![allow(dead_code)]
+
+use anchor_lang::{
+    prelude::{ AccountInfo, Pubkey },
+    solana_program::{ entrypoint::ProgramResult, hash::hash, instruction::Instruction },
+    AccountSerialize, InstructionData, ToAccountMetas,
+};
+use solana_program_test::{ processor, ProgramTest, ProgramTestContext };
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{ AccountMeta, InstructionError },
+    program_pack::Pack,
+    rent::Rent,
+    signature::{ Keypair, Signer },
+    system_instruction, system_program,
+    transaction::{ Transaction, TransactionError },
+    transport::TransportError,
+};
+use vyper_core::state::TrancheConfig;
+use vyper_otc::state::{ GlobalConfig, OtcState };
+use vyper_otc_client::{
+    decode_otc_state, decode_tranche_config, find_global_config, find_otc_authority,
+    InitializeAccounts, InitializeInputData, OtcAccounts, VyperCoreAccounts,
+};
+
+pub const FEE_BPS: u16 = 100;
+pub const SETTLE_GRACE_PERIOD: i64 = 86_400;
+pub const START_TIMESTAMP: i64 = 1_650_000_000;
+pub const STRIKE: f64 = 5000.;
+pub const RESERVE_DECIMALS: u8 = 6;
+
+// anchor entrypoints need the accounts to live as long as the program invocation
+macro_rules! anchor_processor {
+    ($program:ident) => {{
+        fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+            let accounts = Box::leak(Box::new(accounts.to_vec()));
+            $program::entry(program_id, accounts, data)
+        }
+        processor!(process_instruction)
+    }};
+}
+
+pub struct User {
+    pub keypair: Keypair,
+    pub reserve_token_account: Pubkey,
+}
+
+impl User {
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+}
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub admin: Keypair,
+    pub mint_authority: Keypair,
+    pub reserve_mint: Pubkey,
+    pub treasury_token_account: Pubkey,
+    pub now: i64,
+    slot: u64,
+}
+
+pub fn default_global_config(admin: &Pubkey) -> GlobalConfig {
+    GlobalConfig {
+        admin: *admin,
+        pending_admin: None,
+        fee_bps: FEE_BPS,
+        treasury: *admin,
+        min_duration: None,
+        max_duration: None,
+        settle_grace_period: SETTLE_GRACE_PERIOD,
+        allowed_rate_programs: vec![],
+        allowed_redeem_logic_programs: vec![],
+        paused: false,
+        bump: find_global_config().1,
+        version: [0, 1, 2],
+    }
+}
+
+pub async fn setup() -> TestEnv {
+    setup_with_global_config(|_| {}).await
+}
+
+pub async fn setup_with_global_config(update: impl FnOnce(&mut GlobalConfig)) -> TestEnv {
+    start(update, false).await
+}
+
+/// the reserve mint is the native mint, users are funded in lamports only
+pub async fn setup_native() -> TestEnv {
+    start(|_| {}, true).await
+}
+
+async fn start(update: impl FnOnce(&mut GlobalConfig), native: bool) -> TestEnv {
+    let mut program_test = ProgramTest::new("vyper_otc", vyper_otc::ID, anchor_processor!(vyper_otc));
+    program_test.add_program("vyper_core", vyper_core::ID, anchor_processor!(vyper_core));
+    program_test.add_program("rate_mock", rate_mock::ID, anchor_processor!(rate_mock));
+    program_test.add_program(
+        "redeem_logic_vanilla_option",
+        redeem_logic_vanilla_option::ID,
+        anchor_processor!(redeem_logic_vanilla_option),
+    );
+
+    let admin = Keypair::new();
+    program_test.add_account(admin.pubkey(), Account {
+        lamports: 10_000_000_000,
+        owner: system_program::ID,
+        ..Account::default()
+    });
+
+    let mut global_config = default_global_config(&admin.pubkey());
+    update(&mut global_config);
+    let mut data = Vec::with_capacity(GlobalConfig::LEN);
+    global_config.try_serialize(&mut data).unwrap();
+    data.resize(GlobalConfig::LEN, 0);
+    program_test.add_account(find_global_config().0, Account {
+        lamports: Rent::default().minimum_balance(GlobalConfig::LEN),
+        data,
+        owner: vyper_otc::ID,
+        ..Account::default()
+    });
+
+    let mut native_mint_data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        decimals: 9,
+        is_initialized: true,
+        ..spl_token::state::Mint::default()
+    }.pack_into_slice(&mut native_mint_data);
+    program_test.add_account(spl_token::native_mint::ID, Account {
+        lamports: Rent::default().minimum_balance(spl_token::state::Mint::LEN),
+        data: native_mint_data,
+        owner: spl_token::ID,
+        ..Account::default()
+    });
+
+    let mut context = program_test.start_with_context().await;
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let mut env = TestEnv {
+        context,
+        admin,
+        mint_authority: Keypair::new(),
+        reserve_mint: Pubkey::default(),
+        treasury_token_account: Pubkey::default(),
+        now: START_TIMESTAMP,
+        slot: clock.slot,
+    };
+    env.set_timestamp(START_TIMESTAMP).await;
+
+    if native {
+        env.reserve_mint = spl_token::native_mint::ID;
+        env.treasury_token_account = env.create_token_account(&env.admin.pubkey()).await;
+        return env;
+    }
+
+    // reserve mint and treasury token account
+    let reserve_mint = Keypair::new();
+    let rent = Rent::default();
+    env.process(&[
+        system_instruction::create_account(
+            &env.context.payer.pubkey(),
+            &reserve_mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint(
+            &spl_token::ID,
+            &reserve_mint.pubkey(),
+            &env.mint_authority.pubkey(),
+            None,
+            RESERVE_DECIMALS,
+        ).unwrap(),
+    ], &[&reserve_mint]).await.unwrap();
+    env.reserve_mint = reserve_mint.pubkey();
+    env.treasury_token_account = env.create_token_account(&env.admin.pubkey()).await;
+
+    env
+}
+
+pub fn assert_otc_error(result: Result<(), TransportError>, error: vyper_otc::errors::VyperOtcErrorCode) {
+    assert_custom_error(result, anchor_lang::error::ERROR_CODE_OFFSET + error as u32);
+}
+
+pub fn assert_custom_error(result: Result<(), TransportError>, code: u32) {
+    match result {
+        Err(TransportError::TransactionError(TransactionError::InstructionError(_, InstructionError::Custom(err)))) => {
+            assert_eq!(err, code, "unexpected error code");
+        }
+        other => panic!("expected custom error {}, got {:?}", code, other),
+    }
+}
+
+fn sighash(name: &str) -> Vec<u8> {
+    hash(format!("global:{}", name).as_bytes()).to_bytes()[..8].to_vec()
+}
+
+impl TestEnv {
+
+    // - - - - - - - - - - - -
+    // Runtime
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// warps the clock, the deposit and settle windows are checked on the unix timestamp
+    pub async fn set_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+        self.now = unix_timestamp;
+    }
+
+    /// each transaction runs on a new slot, so that the same instructions can be sent twice
+    pub async fn process(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Result<(), TransportError> {
+        self.slot += 1;
+        self.context.warp_to_slot(self.slot).unwrap();
+        self.set_timestamp(self.now).await;
+
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&self.context.payer.pubkey()), &all_signers, blockhash);
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn account_data(&mut self, key: &Pubkey) -> Option<Vec<u8>> {
+        self.context.banks_client.get_account(*key).await.unwrap().map(|account| account.data)
+    }
+
+    // - - - - - - - - - - - -
+    // Tokens
+
+    pub async fn create_token_account(&mut self, owner: &Pubkey) -> Pubkey {
+        let payer = self.payer();
+        self.process(&[spl_associated_token_account::create_associated_token_account(
+            &payer,
+            owner,
+            &self.reserve_mint,
+        )], &[]).await.unwrap();
+        spl_associated_token_account::get_associated_token_address(owner, &self.reserve_mint)
+    }
+
+    pub async fn create_user(&mut self, reserve_amount: u64) -> User {
+        let keypair = Keypair::new();
+        let payer = self.payer();
+        self.process(&[system_instruction::transfer(&payer, &keypair.pubkey(), 1_000_000_000)], &[])
+            .await
+            .unwrap();
+        let reserve_token_account = self.create_token_account(&keypair.pubkey()).await;
+        if reserve_amount > 0 {
+            let mint_authority = Keypair::from_bytes(&self.mint_authority.to_bytes()).unwrap();
+            self.process(&[spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &self.reserve_mint,
+                &reserve_token_account,
+                &mint_authority.pubkey(),
+                &[],
+                reserve_amount,
+            ).unwrap()], &[&mint_authority]).await.unwrap();
+        }
+        User { keypair, reserve_token_account }
+    }
+
+    pub async fn lamports(&mut self, key: &Pubkey) -> u64 {
+        self.context.banks_client.get_balance(*key).await.unwrap()
+    }
+
+    pub async fn token_balance(&mut self, token_account: &Pubkey) -> u64 {
+        match self.account_data(token_account).await {
+            Some(data) => spl_token::state::Account::unpack(&data).unwrap().amount,
+            None => 0,
+        }
+    }
+
+    // - - - - - - - - - - - -
+    // OTC
+
+    pub fn default_input(&self) -> InitializeInputData {
+        InitializeInputData {
+            senior_deposit_amount: 1_000,
+            junior_deposit_amount: 1_000,
+            deposit_start: None,
+            deposit_end: self.now + 100,
+            settle_start: self.now + 200,
+            settle_deadline: None,
+            senior_counterparty: None,
+            junior_counterparty: None,
+            settle_bounty: 0,
+        }
+    }
+
+    /// creates the plugins states, the vyper tranche configuration and the otc
+    pub async fn create_otc(&mut self, input_data: InitializeInputData) -> Result<Pubkey, TransportError> {
+        let payer = self.payer();
+
+        // vanilla option redeem logic: strike, notional, is_call, is_linear
+        let redeem_logic_state = Keypair::new();
+        let mut redeem_logic_data = sighash("initialize");
+        redeem_logic_data.extend_from_slice(&STRIKE.to_le_bytes());
+        redeem_logic_data.extend_from_slice(&1f64.to_le_bytes());
+        redeem_logic_data.push(1);
+        redeem_logic_data.push(1);
+        let redeem_logic_init_ix = Instruction {
+            program_id: redeem_logic_vanilla_option::ID,
+            accounts: vec![
+                AccountMeta::new(redeem_logic_state.pubkey(), true),
+                AccountMeta::new_readonly(payer, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data: redeem_logic_data,
+        };
+
+        // mock rate, the fair value is set right before settle
+        let rate_state = Keypair::new();
+        let rate_init_ix = Instruction {
+            program_id: rate_mock::ID,
+            accounts: rate_mock::accounts::InitializeContext {
+                rate_data: rate_state.pubkey(),
+                authority: payer,
+                signer: payer,
+                system_program: system_program::ID,
+            }.to_account_metas(None),
+            data: rate_mock::instruction::Initialize {}.data(),
+        };
+        self.process(&[redeem_logic_init_ix, rate_init_ix], &[&redeem_logic_state, &rate_state]).await?;
+
+        // vyper tranche configuration owned by the otc authority
+        let otc_state = Keypair::new();
+        let tranche_config = Keypair::new();
+        let senior_tranche_mint = Keypair::new();
+        let junior_tranche_mint = Keypair::new();
+        let vyper = VyperCoreAccounts::new(
+            tranche_config.pubkey(),
+            self.reserve_mint,
+            senior_tranche_mint.pubkey(),
+            junior_tranche_mint.pubkey(),
+            rate_mock::ID,
+            rate_state.pubkey(),
+            redeem_logic_vanilla_option::ID,
+            redeem_logic_state.pubkey(),
+        );
+        self.process(&[vyper_otc_client::initialize_vyper_tranche_config(
+            &payer,
+            &find_otc_authority(&otc_state.pubkey()).0,
+            &vyper,
+            RESERVE_DECIMALS,
+        )], &[&tranche_config, &senior_tranche_mint, &junior_tranche_mint]).await?;
+
+        self.initialize_otc(&otc_state, &vyper, input_data).await?;
+        Ok(otc_state.pubkey())
+    }
+
+    pub async fn initialize_otc(
+        &mut self,
+        otc_state: &Keypair,
+        vyper: &VyperCoreAccounts,
+        input_data: InitializeInputData,
+    ) -> Result<(), TransportError> {
+        let payer = self.payer();
+        let otc_senior_reserve_token_account = Keypair::new();
+        let otc_junior_reserve_token_account = Keypair::new();
+        let otc_senior_tranche_token_account = Keypair::new();
+        let otc_junior_tranche_token_account = Keypair::new();
+        let ix = vyper_otc_client::initialize(
+            &payer,
+            &InitializeAccounts {
+                otc_state: otc_state.pubkey(),
+                otc_senior_reserve_token_account: otc_senior_reserve_token_account.pubkey(),
+                otc_junior_reserve_token_account: otc_junior_reserve_token_account.pubkey(),
+                otc_senior_tranche_token_account: otc_senior_tranche_token_account.pubkey(),
+                otc_junior_tranche_token_account: otc_junior_tranche_token_account.pubkey(),
+            },
+            vyper,
+            input_data,
+        );
+        self.process(&[ix], &[
+            otc_state,
+            &otc_senior_reserve_token_account,
+            &otc_junior_reserve_token_account,
+            &otc_senior_tranche_token_account,
+            &otc_junior_tranche_token_account,
+        ]).await
+    }
+
+    pub async fn otc_state(&mut self, otc_state: &Pubkey) -> Option<OtcState> {
+        self.account_data(otc_state).await.map(|data| decode_otc_state(&data).unwrap())
+    }
+
+    pub async fn otc_accounts(&mut self, otc_state: &Pubkey) -> OtcAccounts {
+        let state = self.otc_state(otc_state).await.expect("otc state not found");
+        let tranche_config = self.tranche_config(&state.vyper_tranche_config).await;
+        OtcAccounts::resolve(*otc_state, state, &tranche_config)
+    }
+
+    pub async fn tranche_config(&mut self, tranche_config: &Pubkey) -> TrancheConfig {
+        let data = self.account_data(tranche_config).await.expect("tranche config not found");
+        decode_tranche_config(&data).unwrap()
+    }
+
+    pub async fn deposit(&mut self, otc_state: &Pubkey, user: &User, is_senior_side: bool) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        let ix = otc.deposit(
+            &user.pubkey(),
+            &user.reserve_token_account,
+            &user.reserve_token_account,
+            &user.pubkey(),
+            &self.treasury_token_account,
+            is_senior_side,
+            false,
+        );
+        self.process(&[ix], &[&user.keypair]).await
+    }
+
+    /// deposits lamports of `user`, the reserve mint needs to be the native mint
+    pub async fn deposit_native(&mut self, otc_state: &Pubkey, user: &User, is_senior_side: bool) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        let ix = otc.deposit(
+            &user.pubkey(),
+            &user.reserve_token_account,
+            &user.reserve_token_account,
+            &user.pubkey(),
+            &self.treasury_token_account,
+            is_senior_side,
+            true,
+        );
+        self.process(&[ix], &[&user.keypair]).await
+    }
+
+    pub async fn withdraw(&mut self, otc_state: &Pubkey, user: &User, is_senior_side: bool) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        let ix = otc.withdraw(&user.pubkey(), &user.reserve_token_account, &user.reserve_token_account, is_senior_side, false);
+        self.process(&[ix], &[&user.keypair]).await
+    }
+
+    /// sets the mock rate, refreshes the vyper fair value and settles in the same transaction
+    pub async fn settle(&mut self, otc_state: &Pubkey, fair_value: f64) -> Result<(), TransportError> {
+        self.settle_by(otc_state, fair_value, None).await
+    }
+
+    /// settle signed by `keeper` instead of the payer
+    pub async fn settle_by(&mut self, otc_state: &Pubkey, fair_value: f64, keeper: Option<&Keypair>) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        let payer = self.payer();
+        let signer = keeper.map_or(payer, |keeper| keeper.pubkey());
+        let set_fair_value_ix = self.set_fair_value_ix(&otc, fair_value);
+        let signers: Vec<&Keypair> = keeper.into_iter().collect();
+        self.process(&[set_fair_value_ix, otc.refresh_tranche_fair_value(), otc.settle(&signer)], &signers).await
+    }
+
+    /// rate mock update, signed by the payer
+    fn set_fair_value_ix(&self, otc: &OtcAccounts, fair_value: f64) -> Instruction {
+        Instruction {
+            program_id: rate_mock::ID,
+            accounts: rate_mock::accounts::SetFairValueContext {
+                rate_data: otc.vyper.rate_program_state,
+                authority: self.payer(),
+            }.to_account_metas(None),
+            data: rate_mock::instruction::SetFairValue { fair_value }.data(),
+        }
+    }
+
+    /// signed by `senior` and `junior`, the fair value is refreshed at `STRIKE` without an agreed split
+    pub async fn early_terminate(
+        &mut self,
+        otc_state: &Pubkey,
+        senior: &User,
+        junior: &User,
+        senior_split_bps: Option<u16>,
+    ) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        let mut ixs = vec![];
+        if senior_split_bps.is_none() {
+            ixs.push(self.set_fair_value_ix(&otc, STRIKE));
+            ixs.push(otc.refresh_tranche_fair_value());
+        }
+        ixs.push(otc.early_terminate(&senior.pubkey(), &junior.pubkey(), senior_split_bps));
+        self.process(&ixs, &[&senior.keypair, &junior.keypair]).await
+    }
+
+    /// unwound by the payer, without refreshing the vyper fair value
+    pub async fn unwind(&mut self, otc_state: &Pubkey) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        let payer = self.payer();
+        self.process(&[otc.unwind(&payer)], &[]).await
+    }
+
+    pub async fn claim(&mut self, otc_state: &Pubkey, user: &User, is_senior_side: bool) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        let ix = otc.claim(&user.pubkey(), &user.reserve_token_account, is_senior_side, false);
+        self.process(&[ix], &[&user.keypair]).await
+    }
+
+    /// claims and closes the wrapped beneficiary token account of `user`
+    pub async fn claim_native(&mut self, otc_state: &Pubkey, user: &User, is_senior_side: bool) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        let ix = otc.claim(&user.pubkey(), &user.reserve_token_account, is_senior_side, true);
+        self.process(&[ix], &[&user.keypair]).await
+    }
+
+    /// distributed by the payer, `beneficiary_owner` needs to hold the position token
+    pub async fn distribute(&mut self, otc_state: &Pubkey, beneficiary_owner: &Pubkey, is_senior_side: bool) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        let payer = self.payer();
+        let ix = otc.distribute(&payer, beneficiary_owner, is_senior_side).expect("side not taken");
+        self.process(&[ix], &[]).await
+    }
+
+    pub async fn close(&mut self, otc_state: &Pubkey) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        let payer = self.payer();
+        self.process(&[otc.close(&payer)], &[]).await
+    }
+
+    /// signed by the payer, the creator, `beneficiary_owner` holds the position token of the taken side
+    /// signed by the creator, the payer, with `position_holder` co-signing to burn its position token
+    pub async fn cancel(&mut self, otc_state: &Pubkey, position_holder: Option<&User>) -> Result<(), TransportError> {
+        let otc = self.otc_accounts(otc_state).await;
+        match position_holder {
+            Some(position_holder) => {
+                let ix = otc.cancel(Some(&position_holder.pubkey()));
+                self.process(&[ix], &[&position_holder.keypair]).await
+            }
+            None => self.process(&[otc.cancel(None)], &[]).await,
+        }
+    }
+
+    pub async fn set_paused(&mut self, paused: bool) -> Result<(), TransportError> {
+        let admin = Keypair::from_bytes(&self.admin.to_bytes()).unwrap();
+        self.process(&[vyper_otc_client::set_paused(&admin.pubkey(), paused)], &[&admin]).await
+    }
+}
@@ -0,0 +1,371 @@
+//! One failing path for each `VyperOtcErrorCode` variant.
+//!
+//! `GenericError` is deliberately left untested: no instruction returns it,
+//! it's only kept so that the error codes of the deployed program don't shift.
+
+mod common;
+
+use common::*;
+use solana_sdk::{ pubkey::Pubkey, signature::{ Keypair, Signer } };
+use vyper_otc::errors::VyperOtcErrorCode;
+use vyper_otc_client::GlobalConfigInputData;
+
+/// creates the position token account of `owner` without any position token
+async fn create_empty_position_token_account(env: &mut TestEnv, position_mint: &Pubkey, owner: &Pubkey) {
+    let payer = env.payer();
+    env.process(&[spl_associated_token_account::create_associated_token_account(&payer, owner, position_mint)], &[])
+        .await
+        .unwrap();
+}
+
+/// otc with both sides taken by `user_a` (senior) and `user_b` (junior)
+async fn matched_otc(env: &mut TestEnv) -> (Pubkey, User, User) {
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+    (otc_state, user_a, user_b)
+}
+
+// - - - - - - - - - - - -
+// Initialize
+
+#[tokio::test]
+async fn initialization_error() {
+    let mut env = setup().await;
+    let mut input = env.default_input();
+    input.settle_start = input.deposit_end;
+    assert_otc_error(env.create_otc(input).await.map(|_| ()), VyperOtcErrorCode::InitializationError);
+}
+
+#[tokio::test]
+async fn math_error() {
+    // the default settle deadline overflows past the settle start
+    let mut env = setup().await;
+    let mut input = env.default_input();
+    input.settle_start = i64::MAX - 1;
+    assert_otc_error(env.create_otc(input).await.map(|_| ()), VyperOtcErrorCode::MathError);
+}
+
+#[tokio::test]
+async fn duration_not_allowed() {
+    let mut env = setup_with_global_config(|global_config| global_config.min_duration = Some(10_000)).await;
+    let input = env.default_input();
+    assert_otc_error(env.create_otc(input).await.map(|_| ()), VyperOtcErrorCode::DurationNotAllowed);
+}
+
+#[tokio::test]
+async fn plugin_program_not_allowed() {
+    let mut env = setup_with_global_config(|global_config| {
+        global_config.allowed_rate_programs = vec![Pubkey::new_unique()]
+    }).await;
+    let input = env.default_input();
+    assert_otc_error(env.create_otc(input).await.map(|_| ()), VyperOtcErrorCode::PluginProgramNotAllowed);
+}
+
+// - - - - - - - - - - - -
+// Global Configuration
+
+#[tokio::test]
+async fn invalid_global_config() {
+    let mut env = setup().await;
+    let admin = Keypair::from_bytes(&env.admin.to_bytes()).unwrap();
+    let ix = vyper_otc_client::update_global_config(&admin.pubkey(), GlobalConfigInputData {
+        admin: admin.pubkey(),
+        fee_bps: 10_001,
+        treasury: admin.pubkey(),
+        min_duration: None,
+        max_duration: None,
+        settle_grace_period: SETTLE_GRACE_PERIOD,
+        allowed_rate_programs: vec![],
+        allowed_redeem_logic_programs: vec![],
+    });
+    assert_otc_error(env.process(&[ix], &[&admin]).await, VyperOtcErrorCode::InvalidGlobalConfig);
+}
+
+#[tokio::test]
+async fn not_pending_admin() {
+    let mut env = setup().await;
+    let user_a = env.create_user(0).await;
+    let ix = vyper_otc_client::accept_admin(&user_a.pubkey());
+    assert_otc_error(env.process(&[ix], &[&user_a.keypair]).await, VyperOtcErrorCode::NotPendingAdmin);
+}
+
+#[tokio::test]
+async fn program_paused() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+
+    env.set_paused(true).await.unwrap();
+    assert_otc_error(env.deposit(&otc_state, &user_a, true).await, VyperOtcErrorCode::ProgramPaused);
+}
+
+// - - - - - - - - - - - -
+// Deposit
+
+#[tokio::test]
+async fn side_already_taken() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    assert_otc_error(env.deposit(&otc_state, &user_b, true).await, VyperOtcErrorCode::SideAlreadyTaken);
+}
+
+#[tokio::test]
+async fn counterparty_not_allowed() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let mut input = env.default_input();
+    input.junior_counterparty = Some(user_b.pubkey());
+    let otc_state = env.create_otc(input).await.unwrap();
+
+    assert_otc_error(env.deposit(&otc_state, &user_a, false).await, VyperOtcErrorCode::CounterpartyNotAllowed);
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+}
+
+#[tokio::test]
+async fn deposit_not_open_yet() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let mut input = env.default_input();
+    input.deposit_start = Some(env.now + 50);
+    let otc_state = env.create_otc(input).await.unwrap();
+
+    assert_otc_error(env.deposit(&otc_state, &user_a, true).await, VyperOtcErrorCode::DepositNotOpenYet);
+}
+
+#[tokio::test]
+async fn deposit_closed() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let input = env.default_input();
+    let otc_state = env.create_otc(input).await.unwrap();
+
+    env.set_timestamp(input.deposit_end + 1).await;
+    assert_otc_error(env.deposit(&otc_state, &user_a, true).await, VyperOtcErrorCode::DepositClosed);
+}
+
+#[tokio::test]
+async fn position_mint_not_found() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+
+    // senior deposit presenting the junior position mint
+    let otc = env.otc_accounts(&otc_state).await;
+    let mut ix = otc.deposit(
+        &user_a.pubkey(),
+        &user_a.reserve_token_account,
+        &user_a.reserve_token_account,
+        &user_a.pubkey(),
+        &env.treasury_token_account,
+        true,
+        false,
+    );
+    for account in ix.accounts.iter_mut() {
+        if account.pubkey == otc.position_mint(true) {
+            account.pubkey = otc.position_mint(false);
+        } else if account.pubkey == otc.position_token_account(true, &user_a.pubkey()) {
+            account.pubkey = otc.position_token_account(false, &user_a.pubkey());
+        }
+    }
+    assert_otc_error(env.process(&[ix], &[&user_a.keypair]).await, VyperOtcErrorCode::PositionMintNotFound);
+}
+
+#[tokio::test]
+async fn native_mint_required() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+    assert_otc_error(env.deposit_native(&otc_state, &user_a, true).await, VyperOtcErrorCode::NativeMintRequired);
+}
+
+// - - - - - - - - - - - -
+// Withdraw
+
+#[tokio::test]
+async fn both_positions_taken() {
+    let mut env = setup().await;
+    let (otc_state, user_a, _) = matched_otc(&mut env).await;
+    assert_otc_error(env.withdraw(&otc_state, &user_a, true).await, VyperOtcErrorCode::BothPositionsTaken);
+}
+
+#[tokio::test]
+async fn beneficiary_not_found() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+
+    // the junior side has not been taken
+    let otc = env.otc_accounts(&otc_state).await;
+    create_empty_position_token_account(&mut env, &otc.position_mint(false), &user_b.pubkey()).await;
+    assert_otc_error(env.withdraw(&otc_state, &user_b, false).await, VyperOtcErrorCode::BeneficiaryNotFound);
+}
+
+#[tokio::test]
+async fn side_already_withdrawn() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let input = env.default_input();
+    let otc_state = env.create_otc(input).await.unwrap();
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+
+    env.set_timestamp(input.deposit_end + 1).await;
+    env.withdraw(&otc_state, &user_a, true).await.unwrap();
+    assert_otc_error(env.withdraw(&otc_state, &user_a, true).await, VyperOtcErrorCode::SideAlreadyWithdrawn);
+}
+
+// - - - - - - - - - - - -
+// Settle
+
+#[tokio::test]
+async fn otc_closed() {
+    let mut env = setup().await;
+    let (otc_state, _, _) = matched_otc(&mut env).await;
+    assert_otc_error(env.settle(&otc_state, 6_000.).await, VyperOtcErrorCode::OtcClosed);
+}
+
+#[tokio::test]
+async fn settle_already_executed() {
+    let mut env = setup().await;
+    let (otc_state, _, _) = matched_otc(&mut env).await;
+    let settle_start = env.otc_state(&otc_state).await.unwrap().settle_start;
+
+    env.set_timestamp(settle_start + 1).await;
+    env.settle(&otc_state, 6_000.).await.unwrap();
+    assert_otc_error(env.settle(&otc_state, 6_000.).await, VyperOtcErrorCode::SettleAlreadyExecuted);
+}
+
+#[tokio::test]
+async fn settle_deadline_not_reached() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let mut input = env.default_input();
+    input.settle_deadline = Some(input.settle_start + 100);
+    let otc_state = env.create_otc(input).await.unwrap();
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+
+    env.set_timestamp(input.settle_start + 100).await;
+    assert_otc_error(env.unwind(&otc_state).await, VyperOtcErrorCode::SettleDeadlineNotReached);
+}
+
+#[tokio::test]
+async fn early_termination_not_available() {
+    let mut env = setup().await;
+    let (otc_state, user_a, user_b) = matched_otc(&mut env).await;
+    let settle_start = env.otc_state(&otc_state).await.unwrap().settle_start;
+
+    env.set_timestamp(settle_start).await;
+    assert_otc_error(
+        env.early_terminate(&otc_state, &user_a, &user_b, Some(5_000)).await,
+        VyperOtcErrorCode::EarlyTerminationNotAvailable,
+    );
+}
+
+#[tokio::test]
+async fn early_terminate_at_the_fair_value_while_paused() {
+    let mut env = setup().await;
+    let (otc_state, user_a, user_b) = matched_otc(&mut env).await;
+
+    env.set_paused(true).await.unwrap();
+    assert_otc_error(env.early_terminate(&otc_state, &user_a, &user_b, None).await, VyperOtcErrorCode::ProgramPaused);
+}
+
+#[tokio::test]
+async fn invalid_split() {
+    let mut env = setup().await;
+    let (otc_state, user_a, user_b) = matched_otc(&mut env).await;
+    assert_otc_error(env.early_terminate(&otc_state, &user_a, &user_b, Some(10_001)).await, VyperOtcErrorCode::InvalidSplit);
+}
+
+// - - - - - - - - - - - -
+// Claim
+
+#[tokio::test]
+async fn settle_not_executed_yet() {
+    let mut env = setup().await;
+    let (otc_state, user_a, _) = matched_otc(&mut env).await;
+    assert_otc_error(env.claim(&otc_state, &user_a, true).await, VyperOtcErrorCode::SettleNotExecutedYet);
+}
+
+#[tokio::test]
+async fn side_already_claimed() {
+    let mut env = setup().await;
+    let (otc_state, user_a, _) = matched_otc(&mut env).await;
+    let settle_start = env.otc_state(&otc_state).await.unwrap().settle_start;
+    env.set_timestamp(settle_start + 1).await;
+    env.settle(&otc_state, 6_000.).await.unwrap();
+
+    env.claim(&otc_state, &user_a, true).await.unwrap();
+    assert_otc_error(env.claim(&otc_state, &user_a, true).await, VyperOtcErrorCode::SideAlreadyClaimed);
+}
+
+#[tokio::test]
+async fn position_token_not_held() {
+    let mut env = setup().await;
+    let (otc_state, _, _) = matched_otc(&mut env).await;
+    let user_c = env.create_user(0).await;
+    let settle_start = env.otc_state(&otc_state).await.unwrap().settle_start;
+    env.set_timestamp(settle_start + 1).await;
+    env.settle(&otc_state, 6_000.).await.unwrap();
+
+    let otc = env.otc_accounts(&otc_state).await;
+    create_empty_position_token_account(&mut env, &otc.position_mint(true), &user_c.pubkey()).await;
+    assert_otc_error(env.claim(&otc_state, &user_c, true).await, VyperOtcErrorCode::PositionTokenNotHeld);
+}
+
+#[tokio::test]
+async fn distribute_requires_the_beneficiary_to_hold_the_position_token() {
+    let mut env = setup().await;
+    let (otc_state, user_a, _) = matched_otc(&mut env).await;
+    let user_c = env.create_user(0).await;
+    let settle_start = env.otc_state(&otc_state).await.unwrap().settle_start;
+    env.set_timestamp(settle_start + 1).await;
+    env.settle(&otc_state, 6_000.).await.unwrap();
+
+    // the position token is moved without transfer_position, the recorded beneficiary is stale
+    let otc = env.otc_accounts(&otc_state).await;
+    let position_mint = otc.position_mint(true);
+    create_empty_position_token_account(&mut env, &position_mint, &user_c.pubkey()).await;
+    env.process(&[spl_token::instruction::transfer(
+        &spl_token::ID,
+        &otc.position_token_account(true, &user_a.pubkey()),
+        &otc.position_token_account(true, &user_c.pubkey()),
+        &user_a.pubkey(),
+        &[],
+        1,
+    ).unwrap()], &[&user_a.keypair]).await.unwrap();
+
+    assert_otc_error(env.distribute(&otc_state, &user_a.pubkey(), true).await, VyperOtcErrorCode::PositionTokenNotHeld);
+    env.claim(&otc_state, &user_c, true).await.unwrap();
+}
+
+// - - - - - - - - - - - -
+// Close
+
+#[tokio::test]
+async fn deposit_open() {
+    let mut env = setup().await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+    assert_otc_error(env.close(&otc_state).await, VyperOtcErrorCode::DepositOpen);
+}
+
+#[tokio::test]
+async fn invalid_otc_status() {
+    let mut env = setup().await;
+    let (otc_state, _, _) = matched_otc(&mut env).await;
+    let deposit_end = env.otc_state(&otc_state).await.unwrap().deposit_end;
+
+    env.set_timestamp(deposit_end + 1).await;
+    assert_otc_error(env.close(&otc_state).await, VyperOtcErrorCode::InvalidOtcStatus);
+}
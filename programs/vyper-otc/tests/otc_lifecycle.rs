@@ -0,0 +1,579 @@
+//! Every instruction on its success path, with the balances moved.
+
+mod common;
+
+use common::*;
+use solana_sdk::{ pubkey::Pubkey, signature::{ Keypair, Signer } };
+use vyper_otc::state::OtcStatus;
+use vyper_otc_client::{ GlobalConfigInputData, find_global_config, decode_global_config };
+
+#[tokio::test]
+async fn deposit_settle_claim_and_close() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+    assert_eq!(state.status, OtcStatus::Open);
+    assert_eq!(state.settle_deadline, state.settle_start + SETTLE_GRACE_PERIOD);
+    assert_eq!(state.senior_side_beneficiary, Some(user_a.reserve_token_account));
+    let otc = env.otc_accounts(&otc_state).await;
+    assert_eq!(env.token_balance(&otc.position_token_account(true, &user_a.pubkey())).await, 1);
+
+    // the protocol fee is charged on both sides once matched
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+    assert_eq!(state.status, OtcStatus::Matched);
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, 0);
+    assert_eq!(env.token_balance(&user_b.reserve_token_account).await, 0);
+    let treasury_token_account = env.treasury_token_account;
+    assert_eq!(env.token_balance(&treasury_token_account).await, 2 * 1_000 * FEE_BPS as u64 / 10_000);
+
+    env.set_timestamp(state.settle_start + 1).await;
+    env.settle(&otc_state, 6_000.).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+    assert_eq!(state.status, OtcStatus::Settled);
+    assert_eq!(state.settle_timestamp, Some(state.settle_start + 1));
+    assert_ne!(state.settle_reserve_fair_value[0], [0; 16]);
+    assert_ne!(state.settle_tranche_fair_value, [[0; 16]; 2]);
+    let tranche_config = env.tranche_config(&state.vyper_tranche_config).await;
+    assert_eq!(state.settle_reserve_fair_value, tranche_config.tranche_data.reserve_fair_value.value);
+    assert_eq!(state.settle_tranche_fair_value, tranche_config.tranche_data.tranche_fair_value.value);
+    assert!(state.senior_redeemed_amount + state.junior_redeemed_amount <= 2 * 1_000 - 2 * 1_000 * FEE_BPS as u64 / 10_000);
+
+    env.claim(&otc_state, &user_a, true).await.unwrap();
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, state.senior_redeemed_amount);
+    assert_eq!(env.token_balance(&otc.position_token_account(true, &user_a.pubkey())).await, 0);
+    assert_eq!(env.otc_state(&otc_state).await.unwrap().status, OtcStatus::Settled);
+
+    env.claim(&otc_state, &user_b, false).await.unwrap();
+    assert_eq!(env.token_balance(&user_b.reserve_token_account).await, state.junior_redeemed_amount);
+    let state = env.otc_state(&otc_state).await.unwrap();
+    assert_eq!(state.status, OtcStatus::Claimed);
+    assert_eq!(state.senior_claimed_amount, Some(state.senior_redeemed_amount));
+    assert_eq!(state.junior_claimed_amount, Some(state.junior_redeemed_amount));
+
+    env.close(&otc_state).await.unwrap();
+    assert!(env.otc_state(&otc_state).await.is_none());
+}
+
+#[tokio::test]
+async fn deposit_without_fee_does_not_need_a_treasury_token_account() {
+    let mut env = setup_with_global_config(|global_config| global_config.fee_bps = 0).await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+
+    env.treasury_token_account = Pubkey::new_unique();
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+    assert_eq!(env.otc_state(&otc_state).await.unwrap().status, OtcStatus::Matched);
+}
+
+#[tokio::test]
+async fn settle_bounty_is_paid_to_the_keeper() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let keeper = env.create_user(0).await;
+    let mut input = env.default_input();
+    input.settle_bounty = 5_000_000;
+    let otc_state = env.create_otc(input).await.unwrap();
+    assert_eq!(env.otc_state(&otc_state).await.unwrap().settle_bounty, 5_000_000);
+
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+    env.set_timestamp(input.settle_start + 1).await;
+    let keeper_lamports = env.lamports(&keeper.pubkey()).await;
+    env.settle_by(&otc_state, 6_000., Some(&keeper.keypair)).await.unwrap();
+
+    assert_eq!(env.lamports(&keeper.pubkey()).await, keeper_lamports + 5_000_000);
+    assert_eq!(env.otc_state(&otc_state).await.unwrap().settle_bounty, 0);
+}
+
+#[tokio::test]
+async fn unpaid_settle_bounty_is_refunded_on_close() {
+    let mut env = setup().await;
+    let mut input = env.default_input();
+    input.settle_bounty = 5_000_000;
+    let otc_state = env.create_otc(input).await.unwrap();
+    let otc_state_lamports = env.lamports(&otc_state).await;
+
+    env.set_timestamp(input.deposit_end + 1).await;
+    let payer = env.payer();
+    let creator_lamports = env.lamports(&payer).await;
+    env.close(&otc_state).await.unwrap();
+
+    // the creator also pays the transaction fee and receives the token accounts rent
+    assert!(otc_state_lamports > 5_000_000);
+    assert!(env.lamports(&payer).await > creator_lamports + otc_state_lamports - 10_000);
+}
+
+#[tokio::test]
+async fn unwind_splits_the_collateral_in_proportion_to_the_deposits() {
+    let mut env = setup().await;
+    let user_a = env.create_user(3_000).await;
+    let user_b = env.create_user(1_000).await;
+    let mut input = env.default_input();
+    input.senior_deposit_amount = 3_000;
+    input.settle_deadline = Some(input.settle_start + 100);
+    let otc_state = env.create_otc(input).await.unwrap();
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+
+    // unwind is available while paused, past the deadline
+    env.set_paused(true).await.unwrap();
+    env.set_timestamp(input.settle_start + 101).await;
+    env.unwind(&otc_state).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+    assert_eq!(state.status, OtcStatus::Settled);
+    assert_eq!(state.senior_redeemed_amount, 3_000 - 3_000 * FEE_BPS as u64 / 10_000);
+    assert_eq!(state.junior_redeemed_amount, 1_000 - 1_000 * FEE_BPS as u64 / 10_000);
+    assert_eq!(state.settle_timestamp, Some(input.settle_start + 101));
+    assert_eq!(state.settle_tranche_fair_value, [[0; 16]; 2]);
+
+    env.claim(&otc_state, &user_a, true).await.unwrap();
+    env.claim(&otc_state, &user_b, false).await.unwrap();
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, state.senior_redeemed_amount);
+    assert_eq!(env.token_balance(&user_b.reserve_token_account).await, state.junior_redeemed_amount);
+}
+
+#[tokio::test]
+async fn early_terminate_pays_the_agreed_split() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+
+    // an agreed split is available while paused
+    env.set_paused(true).await.unwrap();
+    env.early_terminate(&otc_state, &user_a, &user_b, Some(2_500)).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+    let net_deposits = 2 * 1_000 - 2 * 1_000 * FEE_BPS as u64 / 10_000;
+    assert_eq!(state.status, OtcStatus::Settled);
+    assert_eq!(state.senior_redeemed_amount, net_deposits / 4);
+    assert_eq!(state.junior_redeemed_amount, net_deposits - net_deposits / 4);
+
+    env.claim(&otc_state, &user_a, true).await.unwrap();
+    env.claim(&otc_state, &user_b, false).await.unwrap();
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, state.senior_redeemed_amount);
+    assert_eq!(env.token_balance(&user_b.reserve_token_account).await, state.junior_redeemed_amount);
+}
+
+#[tokio::test]
+async fn early_terminate_at_the_current_fair_value() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+
+    env.early_terminate(&otc_state, &user_a, &user_b, None).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+    assert_eq!(state.status, OtcStatus::Settled);
+    assert!(state.senior_redeemed_amount + state.junior_redeemed_amount <= 2 * 1_000 - 2 * 1_000 * FEE_BPS as u64 / 10_000);
+    assert_ne!(state.settle_tranche_fair_value, [[0; 16]; 2]);
+}
+
+#[tokio::test]
+async fn native_sol_is_wrapped_on_deposit_and_unwrapped_on_claim() {
+    let mut env = setup_native().await;
+    let user_a = env.create_user(0).await;
+    let user_b = env.create_user(0).await;
+    let mut input = env.default_input();
+    input.senior_deposit_amount = 100_000_000;
+    input.junior_deposit_amount = 100_000_000;
+    let otc_state = env.create_otc(input).await.unwrap();
+
+    let lamports_before = env.lamports(&user_a.pubkey()).await;
+    env.deposit_native(&otc_state, &user_a, true).await.unwrap();
+    assert!(env.lamports(&user_a.pubkey()).await <= lamports_before - 100_000_000);
+    env.deposit_native(&otc_state, &user_b, false).await.unwrap();
+    let treasury_token_account = env.treasury_token_account;
+    assert_eq!(env.token_balance(&treasury_token_account).await, 2 * 100_000_000 * FEE_BPS as u64 / 10_000);
+
+    env.set_timestamp(input.settle_start + 1).await;
+    env.settle(&otc_state, 6_000.).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+
+    // the wrapped beneficiary token account is closed to the signer
+    let lamports_before = env.lamports(&user_a.pubkey()).await;
+    env.claim_native(&otc_state, &user_a, true).await.unwrap();
+    assert!(env.account_data(&user_a.reserve_token_account).await.is_none());
+    assert!(env.lamports(&user_a.pubkey()).await >= lamports_before + state.senior_redeemed_amount);
+}
+
+#[tokio::test]
+async fn distribute_pays_the_recorded_beneficiaries() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+    let settle_start = env.otc_state(&otc_state).await.unwrap().settle_start;
+    env.set_timestamp(settle_start + 1).await;
+    env.settle(&otc_state, 6_000.).await.unwrap();
+
+    // only the payer signs
+    env.distribute(&otc_state, &user_a.pubkey(), true).await.unwrap();
+    env.distribute(&otc_state, &user_b.pubkey(), false).await.unwrap();
+
+    let state = env.otc_state(&otc_state).await.unwrap();
+    assert_eq!(state.status, OtcStatus::Claimed);
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, state.senior_redeemed_amount);
+    assert_eq!(env.token_balance(&user_b.reserve_token_account).await, state.junior_redeemed_amount);
+    env.close(&otc_state).await.unwrap();
+}
+
+#[tokio::test]
+async fn withdraw_refunds_and_burns_the_position_token() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    env.withdraw(&otc_state, &user_a, true).await.unwrap();
+
+    let state = env.otc_state(&otc_state).await.unwrap();
+    assert_eq!(state.status, OtcStatus::Open);
+    assert_eq!(state.senior_side_beneficiary, None);
+    assert_eq!(state.senior_withdrawn_amount, 1_000);
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, 1_000);
+    let otc = env.otc_accounts(&otc_state).await;
+    assert_eq!(env.token_balance(&otc.position_token_account(true, &user_a.pubkey())).await, 0);
+
+    // the side is free again
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+
+    // after the deposit window the otc is refunded and can be closed
+    env.set_timestamp(state.deposit_end + 1).await;
+    env.withdraw(&otc_state, &user_a, true).await.unwrap();
+    assert_eq!(env.otc_state(&otc_state).await.unwrap().status, OtcStatus::Refunded);
+    env.close(&otc_state).await.unwrap();
+    assert!(env.otc_state(&otc_state).await.is_none());
+}
+
+#[tokio::test]
+async fn close_without_deposits() {
+    let mut env = setup().await;
+    let input = env.default_input();
+    let otc_state = env.create_otc(input).await.unwrap();
+
+    env.set_timestamp(input.deposit_end + 1).await;
+    env.close(&otc_state).await.unwrap();
+    assert!(env.otc_state(&otc_state).await.is_none());
+}
+
+#[tokio::test]
+async fn close_sweeps_reserve_dust_to_the_creator() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1).await;
+    let input = env.default_input();
+    let otc_state = env.create_otc(input).await.unwrap();
+    let payer = env.payer();
+    let creator_reserve_token_account = env.create_token_account(&payer).await;
+
+    // anyone can send tokens to the reserve accounts
+    let otc = env.otc_accounts(&otc_state).await;
+    let dust_ix = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &user_a.reserve_token_account,
+        &otc.otc_state.otc_senior_reserve_token_account,
+        &user_a.pubkey(),
+        &[],
+        1,
+    ).unwrap();
+    env.process(&[dust_ix], &[&user_a.keypair]).await.unwrap();
+
+    env.set_timestamp(input.deposit_end + 1).await;
+    env.close(&otc_state).await.unwrap();
+    assert!(env.otc_state(&otc_state).await.is_none());
+    assert_eq!(env.token_balance(&creator_reserve_token_account).await, 1);
+}
+
+#[tokio::test]
+async fn deposit_for_a_third_party_beneficiary() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let user_c = env.create_user(0).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+
+    // the position token goes to the beneficiary owner, not to the depositor
+    let otc = env.otc_accounts(&otc_state).await;
+    let treasury_token_account = env.treasury_token_account;
+    let ix = otc.deposit(
+        &user_a.pubkey(),
+        &user_a.reserve_token_account,
+        &user_c.reserve_token_account,
+        &user_c.pubkey(),
+        &treasury_token_account,
+        true,
+        false,
+    );
+    env.process(&[ix], &[&user_a.keypair]).await.unwrap();
+    assert_eq!(env.otc_state(&otc_state).await.unwrap().senior_side_beneficiary, Some(user_c.reserve_token_account));
+    assert_eq!(env.token_balance(&otc.position_token_account(true, &user_c.pubkey())).await, 1);
+    assert!(env.account_data(&otc.position_token_account(true, &user_a.pubkey())).await.is_none());
+
+    // the recorded beneficiary can be paid without its signature
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+    env.set_timestamp(state.settle_start + 1).await;
+    env.settle(&otc_state, 6_000.).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+    env.distribute(&otc_state, &user_c.pubkey(), true).await.unwrap();
+    assert_eq!(env.token_balance(&user_c.reserve_token_account).await, state.senior_redeemed_amount);
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, 0);
+}
+
+#[tokio::test]
+async fn cancel_refunds_the_taken_side() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+
+    env.deposit(&otc_state, &user_a, false).await.unwrap();
+    let otc = env.otc_accounts(&otc_state).await;
+    env.cancel(&otc_state, Some(&user_a)).await.unwrap();
+
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, 1_000);
+    assert_eq!(env.token_balance(&otc.position_token_account(false, &user_a.pubkey())).await, 0);
+    assert!(env.otc_state(&otc_state).await.is_none());
+}
+
+#[tokio::test]
+async fn cancel_sweeps_reserve_donations_to_the_creator() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(2).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+    let payer = env.payer();
+    let creator_reserve_token_account = env.create_token_account(&payer).await;
+    env.deposit(&otc_state, &user_a, false).await.unwrap();
+
+    // anyone can send tokens to the reserve accounts, taken or not
+    let otc = env.otc_accounts(&otc_state).await;
+    let donation_ixs = [
+        otc.otc_state.otc_senior_reserve_token_account,
+        otc.otc_state.otc_junior_reserve_token_account,
+    ].map(|reserve_token_account| spl_token::instruction::transfer(
+        &spl_token::ID,
+        &user_b.reserve_token_account,
+        &reserve_token_account,
+        &user_b.pubkey(),
+        &[],
+        1,
+    ).unwrap());
+    env.process(&donation_ixs, &[&user_b.keypair]).await.unwrap();
+
+    env.cancel(&otc_state, None).await.unwrap();
+    assert!(env.otc_state(&otc_state).await.is_none());
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, 1_000);
+    assert_eq!(env.token_balance(&creator_reserve_token_account).await, 2);
+}
+
+#[tokio::test]
+async fn transfer_position_then_claim_by_the_new_holder() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_b = env.create_user(1_000).await;
+    let user_c = env.create_user(0).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    let otc = env.otc_accounts(&otc_state).await;
+    let ix = otc.transfer_position(
+        &user_a.pubkey(),
+        &user_a.reserve_token_account,
+        &user_c.pubkey(),
+        &user_c.reserve_token_account,
+        true,
+    );
+    env.process(&[ix], &[&user_a.keypair]).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+    assert_eq!(state.senior_side_beneficiary, Some(user_c.reserve_token_account));
+    assert_eq!(env.token_balance(&otc.position_token_account(true, &user_a.pubkey())).await, 0);
+    assert_eq!(env.token_balance(&otc.position_token_account(true, &user_c.pubkey())).await, 1);
+
+    env.deposit(&otc_state, &user_b, false).await.unwrap();
+    env.set_timestamp(state.settle_start + 1).await;
+    env.settle(&otc_state, 4_000.).await.unwrap();
+    let state = env.otc_state(&otc_state).await.unwrap();
+
+    env.claim(&otc_state, &user_c, true).await.unwrap();
+    assert_eq!(env.token_balance(&user_c.reserve_token_account).await, state.senior_redeemed_amount);
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, 0);
+}
+
+#[tokio::test]
+async fn transfer_position_by_the_position_holder() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_c = env.create_user(0).await;
+    let user_d = env.create_user(0).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+
+    // the position token is moved without transfer_position, user_a still owns the recorded beneficiary
+    let payer = env.payer();
+    let otc = env.otc_accounts(&otc_state).await;
+    let position_mint = otc.position_mint(true);
+    let ixs = [
+        spl_associated_token_account::create_associated_token_account(&payer, &user_d.pubkey(), &position_mint),
+        spl_token::instruction::transfer(
+            &spl_token::ID,
+            &otc.position_token_account(true, &user_a.pubkey()),
+            &otc.position_token_account(true, &user_d.pubkey()),
+            &user_a.pubkey(),
+            &[],
+            1,
+        ).unwrap(),
+    ];
+    env.process(&ixs, &[&user_a.keypair]).await.unwrap();
+
+    // the holder novates the side, the new owner holds the position token and owns the beneficiary
+    let ix = otc.transfer_position(
+        &user_d.pubkey(),
+        &user_a.reserve_token_account,
+        &user_c.pubkey(),
+        &user_c.reserve_token_account,
+        true,
+    );
+    env.process(&[ix], &[&user_d.keypair]).await.unwrap();
+    assert_eq!(env.otc_state(&otc_state).await.unwrap().senior_side_beneficiary, Some(user_c.reserve_token_account));
+    assert_eq!(env.token_balance(&otc.position_token_account(true, &user_d.pubkey())).await, 0);
+    assert_eq!(env.token_balance(&otc.position_token_account(true, &user_c.pubkey())).await, 1);
+
+    // the previous beneficiary owner can't novate the side anymore
+    let ix = otc.transfer_position(
+        &user_a.pubkey(),
+        &user_c.reserve_token_account,
+        &user_a.pubkey(),
+        &user_a.reserve_token_account,
+        true,
+    );
+    assert!(env.process(&[ix], &[&user_a.keypair]).await.is_err());
+}
+
+#[tokio::test]
+async fn update_beneficiary_then_cancel_refunds_the_new_beneficiary() {
+    let mut env = setup().await;
+    let user_a = env.create_user(1_000).await;
+    let user_c = env.create_user(0).await;
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+
+    env.deposit(&otc_state, &user_a, true).await.unwrap();
+    let new_beneficiary = user_c.reserve_token_account;
+    let otc = env.otc_accounts(&otc_state).await;
+    let ix = otc.update_beneficiary(&user_a.pubkey(), &new_beneficiary, true);
+    env.process(&[ix], &[&user_a.keypair]).await.unwrap();
+    assert_eq!(env.otc_state(&otc_state).await.unwrap().senior_side_beneficiary, Some(new_beneficiary));
+
+    // the creator cancel doesn't depend on the position token holder
+    let payer = env.payer();
+    let position_mint = otc.position_mint(true);
+    let ixs = [
+        spl_associated_token_account::create_associated_token_account(&payer, &user_c.pubkey(), &position_mint),
+        spl_token::instruction::transfer(
+            &spl_token::ID,
+            &otc.position_token_account(true, &user_a.pubkey()),
+            &otc.position_token_account(true, &user_c.pubkey()),
+            &user_a.pubkey(),
+            &[],
+            1,
+        ).unwrap(),
+    ];
+    env.process(&ixs, &[&user_a.keypair]).await.unwrap();
+
+    env.cancel(&otc_state, None).await.unwrap();
+    assert_eq!(env.token_balance(&new_beneficiary).await, 1_000);
+    assert_eq!(env.token_balance(&user_a.reserve_token_account).await, 0);
+}
+
+#[tokio::test]
+async fn admin_updates_and_pauses_the_global_config() {
+    let mut env = setup().await;
+    let admin = env.admin.pubkey();
+    let ix = vyper_otc_client::update_global_config(&admin, GlobalConfigInputData {
+        admin,
+        fee_bps: 50,
+        treasury: admin,
+        min_duration: Some(100),
+        max_duration: None,
+        settle_grace_period: SETTLE_GRACE_PERIOD,
+        allowed_rate_programs: vec![rate_mock::ID],
+        allowed_redeem_logic_programs: vec![],
+    });
+    let admin_keypair = solana_sdk::signature::Keypair::from_bytes(&env.admin.to_bytes()).unwrap();
+    env.process(&[ix], &[&admin_keypair]).await.unwrap();
+
+    env.set_paused(true).await.unwrap();
+    let data = env.account_data(&find_global_config().0).await.unwrap();
+    let global_config = decode_global_config(&data).unwrap();
+    assert_eq!(global_config.fee_bps, 50);
+    assert_eq!(global_config.min_duration, Some(100));
+    assert_eq!(global_config.allowed_rate_programs, vec![rate_mock::ID]);
+    assert!(global_config.paused);
+
+    // otcs on the allowed plugins can be created once resumed
+    env.set_paused(false).await.unwrap();
+    let otc_state = env.create_otc(env.default_input()).await.unwrap();
+    assert_eq!(env.otc_state(&otc_state).await.unwrap().fee_bps, 50);
+}
+
+#[tokio::test]
+async fn admin_rotation_is_accepted_by_the_new_admin() {
+    let mut env = setup().await;
+    let admin = Keypair::from_bytes(&env.admin.to_bytes()).unwrap();
+    let new_admin = Keypair::new();
+    let ix = vyper_otc_client::update_global_config(&admin.pubkey(), GlobalConfigInputData {
+        admin: new_admin.pubkey(),
+        fee_bps: FEE_BPS,
+        treasury: admin.pubkey(),
+        min_duration: None,
+        max_duration: None,
+        settle_grace_period: SETTLE_GRACE_PERIOD,
+        allowed_rate_programs: vec![],
+        allowed_redeem_logic_programs: vec![],
+    });
+    env.process(&[ix], &[&admin]).await.unwrap();
+
+    // the current admin keeps the configuration until the rotation is accepted
+    let global_config = decode_global_config(&env.account_data(&find_global_config().0).await.unwrap()).unwrap();
+    assert_eq!(global_config.admin, admin.pubkey());
+    assert_eq!(global_config.pending_admin, Some(new_admin.pubkey()));
+    env.set_paused(true).await.unwrap();
+
+    env.process(&[vyper_otc_client::accept_admin(&new_admin.pubkey())], &[&new_admin]).await.unwrap();
+    let global_config = decode_global_config(&env.account_data(&find_global_config().0).await.unwrap()).unwrap();
+    assert_eq!(global_config.admin, new_admin.pubkey());
+    assert_eq!(global_config.pending_admin, None);
+    assert!(env.set_paused(false).await.is_err());
+    env.process(&[vyper_otc_client::set_paused(&new_admin.pubkey(), false)], &[&new_admin]).await.unwrap();
+}
+
+#[tokio::test]
+async fn initialize_global_config_runs_once() {
+    // the global configuration is stored at genesis, see `common`
+    let mut env = setup().await;
+    let payer = env.payer();
+    let admin = env.admin.pubkey();
+    let ix = vyper_otc_client::initialize_global_config(&payer, &Pubkey::new_unique(), GlobalConfigInputData {
+        admin,
+        fee_bps: FEE_BPS,
+        treasury: admin,
+        min_duration: None,
+        max_duration: None,
+        settle_grace_period: SETTLE_GRACE_PERIOD,
+        allowed_rate_programs: vec![],
+        allowed_redeem_logic_programs: vec![],
+    });
+    assert!(env.process(&[ix], &[]).await.is_err());
+}
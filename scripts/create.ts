@@ -82,6 +82,19 @@ const main = async () => {
   const depositEnd = nowSeconds + DEPOSIT_EXPIRATION_FROM_NOW_S;
   const settleStart = nowSeconds + SETTLE_AVAILABLE_FROM_NOW_S;
 
+  const [globalConfig] = await anchor.web3.PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("global_config")],
+    program.programId
+  );
+  const [seniorPositionMint] = await anchor.web3.PublicKey.findProgramAddress(
+    [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("senior_position")],
+    program.programId
+  );
+  const [juniorPositionMint] = await anchor.web3.PublicKey.findProgramAddress(
+    [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("junior_position")],
+    program.programId
+  );
+
   const tx = await program.methods
     .initialize({
       seniorDepositAmount: new anchor.BN(USER_A_DEPOSIT_AMOUNT),
@@ -89,11 +102,16 @@ const main = async () => {
       depositStart: null,
       depositEnd: new anchor.BN(depositEnd),
       settleStart: new anchor.BN(settleStart),
+      seniorCounterparty: null,
+      juniorCounterparty: null,
+      settleDeadline: null,
+      settleBounty: new anchor.BN(0),
       description: new Array(128).fill(0),
     })
     .accounts({
       reserveMint: RESERVE_MINT,
       otcAuthority,
+      globalConfig,
       otcState: otcState.publicKey,
       seniorTrancheMint: vyperConfig.seniorTrancheMint,
       juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -102,6 +120,8 @@ const main = async () => {
       otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
       otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
       otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+      seniorPositionMint,
+      juniorPositionMint,
       vyperTrancheConfig: vyperConfig.trancheConfig,
     })
     .signers([
@@ -0,0 +1,24 @@
+import * as anchor from "@project-serum/anchor";
+import { getAssociatedTokenAddress } from "@solana/spl-token";
+import { PublicKey } from "@solana/web3.js";
+
+export type PositionMints = {
+  seniorPositionMint: PublicKey;
+  juniorPositionMint: PublicKey;
+};
+
+export async function findPositionMints(programId: PublicKey, otcState: PublicKey): Promise<PositionMints> {
+  const [seniorPositionMint] = await anchor.web3.PublicKey.findProgramAddress(
+    [otcState.toBuffer(), anchor.utils.bytes.utf8.encode("senior_position")],
+    programId
+  );
+  const [juniorPositionMint] = await anchor.web3.PublicKey.findProgramAddress(
+    [otcState.toBuffer(), anchor.utils.bytes.utf8.encode("junior_position")],
+    programId
+  );
+  return { seniorPositionMint, juniorPositionMint };
+}
+
+export async function getPositionTokenAccount(positionMint: PublicKey, owner: PublicKey): Promise<PublicKey> {
+  return getAssociatedTokenAddress(positionMint, owner);
+}
@@ -0,0 +1,31 @@
+import { AnchorProvider, Program } from "@project-serum/anchor";
+import { Keypair, PublicKey, TransactionInstruction } from "@solana/web3.js";
+import { RateMock } from "../../target/types/rate_mock";
+
+export async function initializeRateMock(program: Program<RateMock>, provider: AnchorProvider, rateData: Keypair) {
+  await program.methods
+    .initialize()
+    .accounts({
+      rateData: rateData.publicKey,
+      authority: provider.wallet.publicKey,
+      signer: provider.wallet.publicKey,
+    })
+    .signers([rateData])
+    .rpc();
+}
+
+// replaces the rate plugin refresh before refreshTrancheFairValue, the provider wallet needs to sign the transaction
+export async function setFairValueInstruction(
+  program: Program<RateMock>,
+  provider: AnchorProvider,
+  rateData: PublicKey,
+  fairValue: number
+): Promise<TransactionInstruction> {
+  return program.methods
+    .setFairValue(fairValue)
+    .accounts({
+      rateData,
+      authority: provider.wallet.publicKey,
+    })
+    .instruction();
+}
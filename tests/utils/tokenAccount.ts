@@ -5,6 +5,7 @@ import { Keypair, PublicKey } from "@solana/web3.js";
 
 export type TokenAccountWrapper = {
   reserveMint: PublicKey;
+  walletTokenAccount: PublicKey;
   users: { user: Keypair; tokenAccount: PublicKey }[];
 };
 
@@ -17,8 +18,16 @@ export async function createTokenAccountWrapper(provider: AnchorProvider, amount
   const sig_createMint = await provider.sendAndConfirm(new anchor.web3.Transaction().add(...createMintIxs), [mint]);
   // console.log("sig: " + sig_createMint);
 
+  const walletTokenAccount = await getAssociatedTokenAddress(mint.publicKey, provider.wallet.publicKey);
+  await provider.sendAndConfirm(
+    new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(provider.wallet.publicKey, walletTokenAccount, provider.wallet.publicKey, mint.publicKey)
+    )
+  );
+
   const res: TokenAccountWrapper = {
     reserveMint: mint.publicKey,
+    walletTokenAccount,
     users: [],
   };
 
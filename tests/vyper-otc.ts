@@ -1,10 +1,13 @@
 import * as anchor from "@project-serum/anchor";
 import { Program } from "@project-serum/anchor";
-import { getAccount } from "@solana/spl-token";
+import {
+  createAssociatedTokenAccountInstruction,
+  createTransferInstruction,
+  getAccount,
+} from "@solana/spl-token";
 import { PublicKey } from "@solana/web3.js";
 import { RustDecimalWrapper } from "@vyper-protocol/rust-decimal-wrapper";
 import { assert, expect } from "chai";
-import { RateSwitchboard, IDL as RateSwitchboardIDL } from "../deps/vyper-core/target/types/rate_switchboard";
 import {
   RedeemLogicVanillaOption,
   IDL as RedeemLogicVanillaOptionIDL,
@@ -14,15 +17,22 @@ import { RateMockPlugin } from "../deps/vyper-core/tests/sdk/plugins/rates/RateM
 import { RedeemLogicLendingPlugin } from "../deps/vyper-core/tests/sdk/plugins/redeemLogic/RedeemLogicLendingPlugin";
 import { RedeemLogicVanillaOptionPlugin } from "../deps/vyper-core/tests/sdk/plugins/redeemLogic/RedeemLogicVanillaOptionPlugin";
 import { createMint } from "../deps/vyper-core/tests/utils";
+import { RateMock, IDL as RateMockIDL } from "../target/types/rate_mock";
 import { VyperOtc } from "../target/types/vyper_otc";
 import sleep from "./utils/sleep";
+import { findPositionMints, getPositionTokenAccount } from "./utils/position";
+import { initializeRateMock, setFairValueInstruction } from "./utils/rateMock";
 import { createTokenAccountWrapper } from "./utils/tokenAccount";
 import { createVyperCoreTrancheConfig } from "./utils/vyperCore";
 
-const RATE_SWITCHBOARD_PROGRAM_ID = new PublicKey("2hGXiH1oEQwjCXRx8bNdHTi49ScZp7Mj2bxcjxtULKe1");
 const REDEEM_LOGIC_VANILLA_OPTION_PROGRAM_ID = new PublicKey("8fSeRtFseNrjdf8quE2YELhuzLkHV7WEGRPA9Jz8xEVe");
 
-const BTC_USD_SWITCHBOARD_AGGREGATOR = new PublicKey("8SXvChNYFhRq4EZuZvnhjrB3jJRQCv4k3P4W6hesH3Ee");
+const RATE_MOCK_PROGRAM_ID = new PublicKey("9TmNGquis5NMjt68j2XgL8qJCiueuH6coEmfHMUf9NUM");
+
+const BPF_UPGRADEABLE_LOADER_PROGRAM_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+// BTC USD fair value set on the rate mock before each settle
+const BTC_USD_FAIR_VALUE = 20000;
 
 describe("vyper-otc", () => {
   // Configure the client to use the local cluster.
@@ -41,14 +51,44 @@ describe("vyper-otc", () => {
     REDEEM_LOGIC_VANILLA_OPTION_PROGRAM_ID,
     provider
   );
-  const rateSwitchboardProgram = new Program<RateSwitchboard>(
-    RateSwitchboardIDL,
-    RATE_SWITCHBOARD_PROGRAM_ID,
-    provider
-  );
+  const rateMockProgram = new Program<RateMock>(RateMockIDL, RATE_MOCK_PROGRAM_ID, provider);
 
   const redeemLogic = RedeemLogicVanillaOptionPlugin.create(redeemLogicVanillaOptionProgram, provider);
 
+  const FEE_BPS = 100;
+  const SETTLE_GRACE_PERIOD = 86400;
+  let globalConfig: PublicKey;
+
+  before(async () => {
+    [globalConfig] = await anchor.web3.PublicKey.findProgramAddress(
+      [anchor.utils.bytes.utf8.encode("global_config")],
+      program.programId
+    );
+    const [programData] = await anchor.web3.PublicKey.findProgramAddress(
+      [program.programId.toBuffer()],
+      BPF_UPGRADEABLE_LOADER_PROGRAM_ID
+    );
+
+    await program.methods
+      .initializeGlobalConfig({
+        admin: provider.wallet.publicKey,
+        feeBps: FEE_BPS,
+        treasury: provider.wallet.publicKey,
+        minDuration: null,
+        maxDuration: null,
+        settleGracePeriod: new anchor.BN(SETTLE_GRACE_PERIOD),
+        allowedRatePrograms: [],
+        allowedRedeemLogicPrograms: [],
+      })
+      .accounts({
+        globalConfig,
+        program: program.programId,
+        programData,
+        signer: provider.wallet.publicKey,
+      })
+      .rpc();
+  });
+
   it("initialize", async () => {
     const reserveMint = await createMint(provider);
     await redeemLogic.initialize(5000, 1, true, true);
@@ -57,29 +97,20 @@ describe("vyper-otc", () => {
       redeemLogic.state
     );
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -106,10 +137,15 @@ describe("vyper-otc", () => {
           depositStart: null,
           depositEnd: new anchor.BN(depositEnd),
           settleStart: new anchor.BN(settleStart),
+          seniorCounterparty: null,
+          juniorCounterparty: null,
+          settleDeadline: null,
+          settleBounty: new anchor.BN(0),
         })
         .accounts({
           reserveMint,
           otcAuthority,
+          globalConfig,
           otcState: otcState.publicKey,
           seniorTrancheMint: vyperConfig.seniorTrancheMint,
           juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -118,6 +154,8 @@ describe("vyper-otc", () => {
           otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
           otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
           otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+          seniorPositionMint,
+          juniorPositionMint,
           vyperTrancheConfig: vyperConfig.trancheConfig,
           vyperCore: vyperCoreProgram.programId,
         })
@@ -136,7 +174,7 @@ describe("vyper-otc", () => {
       expect(otcStateAccount.depositStart.toNumber()).to.be.lt(depositEnd);
       expect(otcStateAccount.depositEnd.toNumber()).to.be.eq(depositEnd);
       expect(otcStateAccount.settleStart.toNumber()).to.be.eq(settleStart);
-      expect(otcStateAccount.settleExecuted).to.be.eq(false);
+      expect(otcStateAccount.status).to.be.eql({ open: {} });
       expect(otcStateAccount.seniorDepositAmount.toNumber()).to.be.eq(seniorDepositAmount);
       expect(otcStateAccount.juniorDepositAmount.toNumber()).to.be.eq(juniorDepositAmount);
       expect(otcStateAccount.seniorSideBeneficiary).to.be.null;
@@ -155,6 +193,8 @@ describe("vyper-otc", () => {
         otcJuniorTrancheTokenAccount.publicKey.toBase58()
       );
       expect(otcStateAccount.otcAuthority.toBase58()).to.be.eql(otcAuthority.toBase58());
+      expect(otcStateAccount.creator.toBase58()).to.be.eql(provider.wallet.publicKey.toBase58());
+      expect(otcStateAccount.feeBps).to.be.eq(FEE_BPS);
     } catch (err) {
       console.error(err);
     }
@@ -170,33 +210,25 @@ describe("vyper-otc", () => {
 
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
     await redeemLogic.initialize(5000, 1, true, true);
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -216,10 +248,15 @@ describe("vyper-otc", () => {
         depositStart: null,
         depositEnd: new anchor.BN(depositEnd),
         settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
       })
       .accounts({
         reserveMint,
         otcAuthority,
+        globalConfig,
         otcState: otcState.publicKey,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
         juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -228,6 +265,8 @@ describe("vyper-otc", () => {
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
         vyperTrancheConfig: vyperConfig.trancheConfig,
         vyperCore: vyperCoreProgram.programId,
       })
@@ -244,16 +283,22 @@ describe("vyper-otc", () => {
     const depositTx = await program.methods
       .deposit({
         isSeniorSide: true,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userA_tokenAccount,
         beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -292,33 +337,25 @@ describe("vyper-otc", () => {
 
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
     await redeemLogic.initialize(5000, 1, true, true);
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -338,10 +375,15 @@ describe("vyper-otc", () => {
         depositStart: null,
         depositEnd: new anchor.BN(depositEnd),
         settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
       })
       .accounts({
         reserveMint,
         otcAuthority,
+        globalConfig,
         otcState: otcState.publicKey,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
         juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -350,6 +392,8 @@ describe("vyper-otc", () => {
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
         vyperTrancheConfig: vyperConfig.trancheConfig,
         vyperCore: vyperCoreProgram.programId,
       })
@@ -366,16 +410,22 @@ describe("vyper-otc", () => {
     const depositATx = await program.methods
       .deposit({
         isSeniorSide: true,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userA_tokenAccount,
         beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -394,16 +444,22 @@ describe("vyper-otc", () => {
     const depositBTx = await program.methods
       .deposit({
         isSeniorSide: false,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userB_tokenAccount,
         beneficiaryTokenAccount: userB_tokenAccount,
+        beneficiaryOwner: userB.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: juniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(juniorPositionMint, userB.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -417,15 +473,7 @@ describe("vyper-otc", () => {
       })
       .signers([userB])
       .preInstructions([
-        await rateSwitchboardProgram.methods
-          .refresh()
-          .accounts({
-            rateData: rateData.publicKey,
-          })
-          .remainingAccounts(
-            [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-          )
-          .instruction(),
+        await setFairValueInstruction(rateMockProgram, provider, rateData.publicKey, BTC_USD_FAIR_VALUE),
         await vyperCoreProgram.methods
           .refreshTrancheFairValue()
           .accounts({
@@ -446,8 +494,15 @@ describe("vyper-otc", () => {
     expect(Number((await getAccount(provider.connection, otcJuniorReserveTokenAccount.publicKey)).amount)).to.be.eq(0);
     expect(Number((await getAccount(provider.connection, otcSeniorTrancheTokenAccount.publicKey)).amount)).to.be.gt(0);
     expect(Number((await getAccount(provider.connection, otcJuniorTrancheTokenAccount.publicKey)).amount)).to.be.gt(0);
+
+    // check protocol fee
+    const seniorFeeAmount = Math.floor((seniorDepositAmount * FEE_BPS) / 10000);
+    const juniorFeeAmount = Math.floor((juniorDepositAmount * FEE_BPS) / 10000);
+    expect(Number((await getAccount(provider.connection, treasuryTokenAccount)).amount)).to.be.eq(
+      seniorFeeAmount + juniorFeeAmount
+    );
     expect(Number((await getAccount(provider.connection, vyperConfig.vyperReserve)).amount)).to.be.eq(
-      seniorDepositAmount + juniorDepositAmount
+      seniorDepositAmount + juniorDepositAmount - seniorFeeAmount - juniorFeeAmount
     );
 
     const otcStateAccount = await program.account.otcState.fetchNullable(otcState.publicKey);
@@ -465,35 +520,27 @@ describe("vyper-otc", () => {
 
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
 
     await redeemLogic.initialize(5000, 1, false, true);
 
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -513,10 +560,15 @@ describe("vyper-otc", () => {
         depositStart: null,
         depositEnd: new anchor.BN(depositEnd),
         settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
       })
       .accounts({
         reserveMint,
         otcAuthority,
+        globalConfig,
         otcState: otcState.publicKey,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
         juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -525,6 +577,8 @@ describe("vyper-otc", () => {
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
         vyperTrancheConfig: vyperConfig.trancheConfig,
         vyperCore: vyperCoreProgram.programId,
       })
@@ -541,16 +595,22 @@ describe("vyper-otc", () => {
     const depositATx = await program.methods
       .deposit({
         isSeniorSide: true,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userA_tokenAccount,
         beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -569,16 +629,22 @@ describe("vyper-otc", () => {
     const depositBTx = await program.methods
       .deposit({
         isSeniorSide: false,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userB_tokenAccount,
         beneficiaryTokenAccount: userB_tokenAccount,
+        beneficiaryOwner: userB.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: juniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(juniorPositionMint, userB.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -592,15 +658,7 @@ describe("vyper-otc", () => {
       })
       .signers([userB])
       .preInstructions([
-        await rateSwitchboardProgram.methods
-          .refresh()
-          .accounts({
-            rateData: rateData.publicKey,
-          })
-          .remainingAccounts(
-            [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-          )
-          .instruction(),
+        await setFairValueInstruction(rateMockProgram, provider, rateData.publicKey, BTC_USD_FAIR_VALUE),
         await vyperCoreProgram.methods
           .refreshTrancheFairValue()
           .accounts({
@@ -615,6 +673,7 @@ describe("vyper-otc", () => {
       ])
       .rpc();
     console.log("user B deposited " + juniorDepositAmount + ". tx: ", depositBTx);
+    expect((await program.account.otcState.fetch(otcState.publicKey)).status).to.be.eql({ matched: {} });
 
     // console.log("senior tranche amount: " + otcSeniorTrancheTokenAccount.publicKey + " -> " + Number((await getAccount(provider.connection, otcSeniorTrancheTokenAccount.publicKey)).amount));
     // console.log("junior tranche amount: " + otcJuniorTrancheTokenAccount.publicKey + " -> " + Number((await getAccount(provider.connection, otcJuniorTrancheTokenAccount.publicKey)).amount));
@@ -628,6 +687,7 @@ describe("vyper-otc", () => {
       .accounts({
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
 
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
@@ -644,15 +704,7 @@ describe("vyper-otc", () => {
         vyperCore: vyperCoreProgram.programId,
       })
       .preInstructions([
-        await rateSwitchboardProgram.methods
-          .refresh()
-          .accounts({
-            rateData: rateData.publicKey,
-          })
-          .remainingAccounts(
-            [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-          )
-          .instruction(),
+        await setFairValueInstruction(rateMockProgram, provider, rateData.publicKey, BTC_USD_FAIR_VALUE),
         await vyperCoreProgram.methods
           .refreshTrancheFairValue()
           .accounts({
@@ -667,32 +719,59 @@ describe("vyper-otc", () => {
       ])
       .rpc();
     console.log("settle tx: ", settleTx);
+    const settledOtcStateAccount = await program.account.otcState.fetch(otcState.publicKey);
+    expect(settledOtcStateAccount.status).to.be.eql({ settled: {} });
+    expect(settledOtcStateAccount.settleTimestamp.toNumber()).to.be.gte(settledOtcStateAccount.settleStart.toNumber());
 
     expect(Number((await getAccount(provider.connection, otcSeniorTrancheTokenAccount.publicKey)).amount)).to.be.eq(0);
     expect(Number((await getAccount(provider.connection, otcJuniorTrancheTokenAccount.publicKey)).amount)).to.be.eq(0);
 
     const claimATx = await program.methods
-      .claim()
+      .claim({ unwrapNative: false })
       .accounts({
         otcAuthority,
         otcState: otcState.publicKey,
         beneficiaryTokenAccount: userA_tokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
         signer: userA.publicKey,
       })
       .signers([userA])
       .rpc();
     console.log("claim A tx: ", claimATx);
 
+    try {
+      await program.methods
+        .claim({ unwrapNative: false })
+        .accounts({
+          otcAuthority,
+          otcState: otcState.publicKey,
+          beneficiaryTokenAccount: userA_tokenAccount,
+          otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+          otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+          positionMint: seniorPositionMint,
+          positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
+          signer: userA.publicKey,
+        })
+        .signers([userA])
+        .rpc();
+      expect(true).to.be.false;
+    } catch (err) {
+      expect(err.error.errorCode.code).to.be.eql("SideAlreadyClaimed");
+    }
+
     const claimBTx = await program.methods
-      .claim()
+      .claim({ unwrapNative: false })
       .accounts({
         otcAuthority,
         otcState: otcState.publicKey,
         beneficiaryTokenAccount: userB_tokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        positionMint: juniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(juniorPositionMint, userB.publicKey),
         signer: userB.publicKey,
       })
       .signers([userB])
@@ -706,6 +785,10 @@ describe("vyper-otc", () => {
     expect(Number((await getAccount(provider.connection, userB_tokenAccount)).amount)).to.be.gte(0);
     expect(Number((await getAccount(provider.connection, otcSeniorReserveTokenAccount.publicKey)).amount)).to.be.eq(0);
     expect(Number((await getAccount(provider.connection, otcJuniorReserveTokenAccount.publicKey)).amount)).to.be.eq(0);
+    const otcStateAccount = await program.account.otcState.fetch(otcState.publicKey);
+    expect(otcStateAccount.status).to.be.eql({ claimed: {} });
+    expect(otcStateAccount.seniorClaimedAmount.toNumber()).to.be.eq(otcStateAccount.seniorRedeemedAmount.toNumber());
+    expect(otcStateAccount.juniorClaimedAmount.toNumber()).to.be.eq(otcStateAccount.juniorRedeemedAmount.toNumber());
   });
 
   it("single deposit and withdraw", async () => {
@@ -718,33 +801,25 @@ describe("vyper-otc", () => {
 
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
     await redeemLogic.initialize(5000, 1, true, true);
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -764,10 +839,15 @@ describe("vyper-otc", () => {
         depositStart: null,
         depositEnd: new anchor.BN(depositEnd),
         settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
       })
       .accounts({
         reserveMint,
         otcAuthority,
+        globalConfig,
         otcState: otcState.publicKey,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
         juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -776,6 +856,8 @@ describe("vyper-otc", () => {
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
         vyperTrancheConfig: vyperConfig.trancheConfig,
         vyperCore: vyperCoreProgram.programId,
       })
@@ -792,16 +874,22 @@ describe("vyper-otc", () => {
     const depositTx = await program.methods
       .deposit({
         isSeniorSide: true,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userA_tokenAccount,
         beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -822,7 +910,7 @@ describe("vyper-otc", () => {
     }
 
     const withdrawTx = await program.methods
-      .withdraw()
+      .withdraw({ unwrapNative: false })
       .accounts({
         userReserveTokenAccount: userA_tokenAccount,
         beneficiaryTokenAccount: userA_tokenAccount,
@@ -830,6 +918,8 @@ describe("vyper-otc", () => {
         otcAuthority,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
         reserveMint,
         signer: userA.publicKey,
       })
@@ -853,34 +943,26 @@ describe("vyper-otc", () => {
 
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
 
     await redeemLogic.initialize(5000, 1, true, true);
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -900,10 +982,15 @@ describe("vyper-otc", () => {
         depositStart: null,
         depositEnd: new anchor.BN(depositEnd),
         settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
       })
       .accounts({
         reserveMint,
         otcAuthority,
+        globalConfig,
         otcState: otcState.publicKey,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
         juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -912,6 +999,8 @@ describe("vyper-otc", () => {
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
         vyperTrancheConfig: vyperConfig.trancheConfig,
         vyperCore: vyperCoreProgram.programId,
       })
@@ -927,16 +1016,22 @@ describe("vyper-otc", () => {
     await program.methods
       .deposit({
         isSeniorSide: true,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userA_tokenAccount,
         beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -955,16 +1050,22 @@ describe("vyper-otc", () => {
       await program.methods
         .deposit({
           isSeniorSide: true,
+          wrapNative: false,
         })
         .accounts({
           userReserveTokenAccount: userB_tokenAccount,
           beneficiaryTokenAccount: userB_tokenAccount,
+          beneficiaryOwner: userB.publicKey,
           otcState: otcState.publicKey,
           otcAuthority,
+          globalConfig,
+          treasuryTokenAccount,
           otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
           otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
           otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
           otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+          positionMint: seniorPositionMint,
+          positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userB.publicKey),
 
           reserveMint,
           seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -978,15 +1079,7 @@ describe("vyper-otc", () => {
         })
         .signers([userB])
         .preInstructions([
-          await rateSwitchboardProgram.methods
-            .refresh()
-            .accounts({
-              rateData: rateData.publicKey,
-            })
-            .remainingAccounts(
-              [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-            )
-            .instruction(),
+          await setFairValueInstruction(rateMockProgram, provider, rateData.publicKey, BTC_USD_FAIR_VALUE),
           await vyperCoreProgram.methods
             .refreshTrancheFairValue()
             .accounts({
@@ -1016,33 +1109,25 @@ describe("vyper-otc", () => {
     await redeemLogic.initialize(5000, 1, true, true);
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
 
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -1061,10 +1146,15 @@ describe("vyper-otc", () => {
         depositStart: null,
         depositEnd: new anchor.BN(depositEnd),
         settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
       })
       .accounts({
         reserveMint,
         otcAuthority,
+        globalConfig,
         otcState: otcState.publicKey,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
         juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -1073,6 +1163,8 @@ describe("vyper-otc", () => {
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
         vyperTrancheConfig: vyperConfig.trancheConfig,
         vyperCore: vyperCoreProgram.programId,
       })
@@ -1088,16 +1180,22 @@ describe("vyper-otc", () => {
     await program.methods
       .deposit({
         isSeniorSide: true,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userA_tokenAccount,
         beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -1115,16 +1213,22 @@ describe("vyper-otc", () => {
     await program.methods
       .deposit({
         isSeniorSide: false,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userB_tokenAccount,
         beneficiaryTokenAccount: userB_tokenAccount,
+        beneficiaryOwner: userB.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: juniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(juniorPositionMint, userB.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -1138,15 +1242,7 @@ describe("vyper-otc", () => {
       })
       .signers([userB])
       .preInstructions([
-        await rateSwitchboardProgram.methods
-          .refresh()
-          .accounts({
-            rateData: rateData.publicKey,
-          })
-          .remainingAccounts(
-            [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-          )
-          .instruction(),
+        await setFairValueInstruction(rateMockProgram, provider, rateData.publicKey, BTC_USD_FAIR_VALUE),
         await vyperCoreProgram.methods
           .refreshTrancheFairValue()
           .accounts({
@@ -1167,6 +1263,7 @@ describe("vyper-otc", () => {
         .accounts({
           otcState: otcState.publicKey,
           otcAuthority,
+          globalConfig,
 
           otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
           otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
@@ -1183,15 +1280,7 @@ describe("vyper-otc", () => {
           vyperCore: vyperCoreProgram.programId,
         })
         .preInstructions([
-          await rateSwitchboardProgram.methods
-            .refresh()
-            .accounts({
-              rateData: rateData.publicKey,
-            })
-            .remainingAccounts(
-              [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-            )
-            .instruction(),
+          await setFairValueInstruction(rateMockProgram, provider, rateData.publicKey, BTC_USD_FAIR_VALUE),
           await vyperCoreProgram.methods
             .refreshTrancheFairValue()
             .accounts({
@@ -1221,33 +1310,25 @@ describe("vyper-otc", () => {
     await redeemLogic.initialize(5000, 1, true, true);
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
 
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -1266,10 +1347,15 @@ describe("vyper-otc", () => {
         depositStart: null,
         depositEnd: new anchor.BN(depositEnd),
         settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
       })
       .accounts({
         reserveMint,
         otcAuthority,
+        globalConfig,
         otcState: otcState.publicKey,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
         juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -1278,6 +1364,8 @@ describe("vyper-otc", () => {
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
         vyperTrancheConfig: vyperConfig.trancheConfig,
         vyperCore: vyperCoreProgram.programId,
       })
@@ -1298,16 +1386,22 @@ describe("vyper-otc", () => {
       await program.methods
         .deposit({
           isSeniorSide: true,
+          wrapNative: false,
         })
         .accounts({
           userReserveTokenAccount: userA_tokenAccount,
           beneficiaryTokenAccount: userA_tokenAccount,
+          beneficiaryOwner: userA.publicKey,
           otcState: otcState.publicKey,
           otcAuthority,
+          globalConfig,
+          treasuryTokenAccount,
           otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
           otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
           otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
           otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+          positionMint: seniorPositionMint,
+          positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
 
           reserveMint,
           seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -1328,7 +1422,7 @@ describe("vyper-otc", () => {
     }
   });
 
-  it("error on claim with wrong beneficiary", async () => {
+  it("claim by the position token holder", async () => {
     // input data
     const seniorDepositAmount = 1000;
     const juniorDepositAmount = 1000;
@@ -1338,6 +1432,7 @@ describe("vyper-otc", () => {
     await redeemLogic.initialize(5000, 1, true, true);
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [
         { user: userA, tokenAccount: userA_tokenAccount },
         { user: userB, tokenAccount: userB_tokenAccount },
@@ -1346,29 +1441,20 @@ describe("vyper-otc", () => {
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount, 1000]);
 
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -1387,10 +1473,15 @@ describe("vyper-otc", () => {
         depositStart: null,
         depositEnd: new anchor.BN(depositEnd),
         settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
       })
       .accounts({
         reserveMint,
         otcAuthority,
+        globalConfig,
         otcState: otcState.publicKey,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
         juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -1399,6 +1490,8 @@ describe("vyper-otc", () => {
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
         vyperTrancheConfig: vyperConfig.trancheConfig,
         vyperCore: vyperCoreProgram.programId,
       })
@@ -1415,16 +1508,22 @@ describe("vyper-otc", () => {
     const depositATx = await program.methods
       .deposit({
         isSeniorSide: true,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userA_tokenAccount,
         beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -1443,16 +1542,22 @@ describe("vyper-otc", () => {
     const depositBTx = await program.methods
       .deposit({
         isSeniorSide: false,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userB_tokenAccount,
         beneficiaryTokenAccount: userB_tokenAccount,
+        beneficiaryOwner: userB.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: juniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(juniorPositionMint, userB.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -1466,15 +1571,7 @@ describe("vyper-otc", () => {
       })
       .signers([userB])
       .preInstructions([
-        await rateSwitchboardProgram.methods
-          .refresh()
-          .accounts({
-            rateData: rateData.publicKey,
-          })
-          .remainingAccounts(
-            [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-          )
-          .instruction(),
+        await setFairValueInstruction(rateMockProgram, provider, rateData.publicKey, BTC_USD_FAIR_VALUE),
         await vyperCoreProgram.methods
           .refreshTrancheFairValue()
           .accounts({
@@ -1499,6 +1596,7 @@ describe("vyper-otc", () => {
       .accounts({
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
 
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
@@ -1515,15 +1613,7 @@ describe("vyper-otc", () => {
         vyperCore: vyperCoreProgram.programId,
       })
       .preInstructions([
-        await rateSwitchboardProgram.methods
-          .refresh()
-          .accounts({
-            rateData: rateData.publicKey,
-          })
-          .remainingAccounts(
-            [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-          )
-          .instruction(),
+        await setFairValueInstruction(rateMockProgram, provider, rateData.publicKey, BTC_USD_FAIR_VALUE),
         await vyperCoreProgram.methods
           .refreshTrancheFairValue()
           .accounts({
@@ -1542,23 +1632,69 @@ describe("vyper-otc", () => {
     expect(Number((await getAccount(provider.connection, otcSeniorTrancheTokenAccount.publicKey)).amount)).to.be.eq(0);
     expect(Number((await getAccount(provider.connection, otcJuniorTrancheTokenAccount.publicKey)).amount)).to.be.eq(0);
 
+    const userA_positionTokenAccount = await getPositionTokenAccount(seniorPositionMint, userA.publicKey);
+    const userC_positionTokenAccount = await getPositionTokenAccount(seniorPositionMint, userC.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          provider.wallet.publicKey,
+          userC_positionTokenAccount,
+          userC.publicKey,
+          seniorPositionMint
+        )
+      )
+    );
+
+    // user C doesn't hold the senior position token yet
     try {
       await program.methods
-        .claim()
+        .claim({ unwrapNative: false })
         .accounts({
           otcAuthority,
           otcState: otcState.publicKey,
           beneficiaryTokenAccount: userC_tokenAccount,
           otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
           otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+          positionMint: seniorPositionMint,
+          positionTokenAccount: userC_positionTokenAccount,
           signer: userC.publicKey,
         })
         .signers([userC])
         .rpc();
       expect(true).to.be.false;
     } catch (err) {
-      expect(err.error.errorCode.code).to.be.eql("BeneficiaryNotFound");
+      expect(err.error.errorCode.code).to.be.eql("PositionTokenNotHeld");
     }
+
+    // user A sells the senior position to user C
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createTransferInstruction(userA_positionTokenAccount, userC_positionTokenAccount, userA.publicKey, 1)
+      ),
+      [userA]
+    );
+
+    const claimTx = await program.methods
+      .claim({ unwrapNative: false })
+      .accounts({
+        otcAuthority,
+        otcState: otcState.publicKey,
+        beneficiaryTokenAccount: userC_tokenAccount,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: userC_positionTokenAccount,
+        signer: userC.publicKey,
+      })
+      .signers([userC])
+      .rpc();
+    console.log("claim tx: ", claimTx);
+
+    const otcStateAccount = await program.account.otcState.fetch(otcState.publicKey);
+    expect(Number((await getAccount(provider.connection, userC_tokenAccount)).amount)).to.be.eq(
+      1000 + otcStateAccount.seniorRedeemedAmount.toNumber()
+    );
+    expect(Number((await getAccount(provider.connection, userC_positionTokenAccount)).amount)).to.be.eq(0);
   });
 
   it("error on 2 deposits and withdraw", async () => {
@@ -1571,35 +1707,27 @@ describe("vyper-otc", () => {
 
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
 
     await redeemLogic.initialize(5000, 1, true, true);
 
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -1619,10 +1747,15 @@ describe("vyper-otc", () => {
         depositStart: null,
         depositEnd: new anchor.BN(depositEnd),
         settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
       })
       .accounts({
         reserveMint,
         otcAuthority,
+        globalConfig,
         otcState: otcState.publicKey,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
         juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -1631,6 +1764,8 @@ describe("vyper-otc", () => {
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
         vyperTrancheConfig: vyperConfig.trancheConfig,
         vyperCore: vyperCoreProgram.programId,
       })
@@ -1647,16 +1782,22 @@ describe("vyper-otc", () => {
     const depositATx = await program.methods
       .deposit({
         isSeniorSide: true,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userA_tokenAccount,
         beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -1675,16 +1816,22 @@ describe("vyper-otc", () => {
     const depositBTx = await program.methods
       .deposit({
         isSeniorSide: false,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userB_tokenAccount,
         beneficiaryTokenAccount: userB_tokenAccount,
+        beneficiaryOwner: userB.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: juniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(juniorPositionMint, userB.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -1698,15 +1845,7 @@ describe("vyper-otc", () => {
       })
       .signers([userB])
       .preInstructions([
-        await rateSwitchboardProgram.methods
-          .refresh()
-          .accounts({
-            rateData: rateData.publicKey,
-          })
-          .remainingAccounts(
-            [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-          )
-          .instruction(),
+        await setFairValueInstruction(rateMockProgram, provider, rateData.publicKey, BTC_USD_FAIR_VALUE),
         await vyperCoreProgram.methods
           .refreshTrancheFairValue()
           .accounts({
@@ -1731,7 +1870,7 @@ describe("vyper-otc", () => {
 
     try {
       const withdrawTx = await program.methods
-        .withdraw()
+        .withdraw({ unwrapNative: false })
         .accounts({
           userReserveTokenAccount: userA_tokenAccount,
           beneficiaryTokenAccount: userA_tokenAccount,
@@ -1739,6 +1878,8 @@ describe("vyper-otc", () => {
           otcAuthority,
           otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
           otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+          positionMint: seniorPositionMint,
+          positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
           reserveMint,
           signer: userA.publicKey,
         })
@@ -1750,7 +1891,7 @@ describe("vyper-otc", () => {
     }
   });
 
-  it("error on withdraw before deposit time window ends", async () => {
+  it("single deposit and withdraw before deposit time window ends", async () => {
     // input data
     const seniorDepositAmount = 10;
     const juniorDepositAmount = 1000;
@@ -1760,35 +1901,27 @@ describe("vyper-otc", () => {
 
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
 
     await redeemLogic.initialize(5000, 1, true, true);
 
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
-      .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
-      })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
-      .rpc();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -1808,10 +1941,15 @@ describe("vyper-otc", () => {
         depositStart: null,
         depositEnd: new anchor.BN(depositEnd),
         settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
       })
       .accounts({
         reserveMint,
         otcAuthority,
+        globalConfig,
         otcState: otcState.publicKey,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
         juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -1820,6 +1958,8 @@ describe("vyper-otc", () => {
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
         vyperTrancheConfig: vyperConfig.trancheConfig,
         vyperCore: vyperCoreProgram.programId,
       })
@@ -1836,16 +1976,22 @@ describe("vyper-otc", () => {
     const depositATx = await program.methods
       .deposit({
         isSeniorSide: true,
+        wrapNative: false,
       })
       .accounts({
         userReserveTokenAccount: userA_tokenAccount,
         beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
         otcState: otcState.publicKey,
         otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
         otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
         otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
         otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
         otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
 
         reserveMint,
         seniorTrancheMint: vyperConfig.seniorTrancheMint,
@@ -1861,66 +2007,1240 @@ describe("vyper-otc", () => {
       .rpc();
     console.log("user A deposited " + seniorDepositAmount + ". tx: ", depositATx);
 
-    try {
-      const withdrawTx = await program.methods
-        .withdraw()
-        .accounts({
-          userReserveTokenAccount: userA_tokenAccount,
-          beneficiaryTokenAccount: userA_tokenAccount,
-          otcState: otcState.publicKey,
-          otcAuthority,
-          otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
-          otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
-          reserveMint,
-          signer: userA.publicKey,
-        })
-        .signers([userA])
-        .rpc();
-      expect(true).to.be.false;
-    } catch (err) {
-      expect(err.error.errorCode.code).to.be.eql("DepositOpen");
-    }
+    const withdrawTx = await program.methods
+      .withdraw({ unwrapNative: false })
+      .accounts({
+        userReserveTokenAccount: userA_tokenAccount,
+        beneficiaryTokenAccount: userA_tokenAccount,
+        otcState: otcState.publicKey,
+        otcAuthority,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
+        reserveMint,
+        signer: userA.publicKey,
+      })
+      .signers([userA])
+      .rpc();
+    console.log("withdraw tx: ", withdrawTx);
+
+    expect(Number((await getAccount(provider.connection, userA_tokenAccount)).amount)).to.be.eq(seniorDepositAmount);
+    expect(Number((await getAccount(provider.connection, otcSeniorReserveTokenAccount.publicKey)).amount)).to.be.eq(0);
+
+    // the senior side is free again
+    const otcStateAccount = await program.account.otcState.fetchNullable(otcState.publicKey);
+    expect(otcStateAccount.seniorSideBeneficiary).to.be.null;
+    expect(otcStateAccount.juniorSideBeneficiary).to.be.null;
   });
 
-  it.only("close contract", async () => {
+  it("deposit only within the deposit time window", async () => {
+    // input data
     const seniorDepositAmount = 1000;
     const juniorDepositAmount = 1000;
+    const nowSeconds = Math.round(Date.now() / 1000); // current UTC timestamp in seconds
+    const depositStart = nowSeconds + 5;
+    const depositEnd = nowSeconds + 10;
+    const settleStart = nowSeconds + 1000;
 
-    // const reserveMint = await createMint(provider);
     const {
       reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
       users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
     } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
 
     await redeemLogic.initialize(5000, 1, true, true);
 
-    const redeemLogicStateAccount = await redeemLogicVanillaOptionProgram.account.redeemLogicConfig.fetch(
-      redeemLogic.state
-    );
     const rateData = anchor.web3.Keypair.generate();
-    await rateSwitchboardProgram.methods
-      .initialize()
+    await initializeRateMock(rateMockProgram, provider, rateData);
+
+    const otcState = anchor.web3.Keypair.generate();
+    const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
+      [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
+      program.programId
+    );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
+
+    const vyperConfig = await createVyperCoreTrancheConfig(
+      provider,
+      vyperCoreProgram,
+      reserveMint,
+      rateMockProgram.programId,
+      rateData.publicKey,
+      redeemLogic.programID,
+      redeemLogic.state,
+      otcAuthority
+    );
+
+    // accounts to create
+    const otcSeniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcSeniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .initialize({
+        seniorDepositAmount: new anchor.BN(seniorDepositAmount),
+        juniorDepositAmount: new anchor.BN(juniorDepositAmount),
+        depositStart: new anchor.BN(depositStart),
+        depositEnd: new anchor.BN(depositEnd),
+        settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
+      })
       .accounts({
-        signer: provider.wallet.publicKey,
-        rateData: rateData.publicKey,
+        reserveMint,
+        otcAuthority,
+        globalConfig,
+        otcState: otcState.publicKey,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperCore: vyperCoreProgram.programId,
       })
-      .remainingAccounts(
-        [BTC_USD_SWITCHBOARD_AGGREGATOR].map((c) => ({ pubkey: c, isSigner: false, isWritable: false }))
-      )
-      .signers([rateData])
+      .signers([
+        otcState,
+        otcSeniorReserveTokenAccount,
+        otcJuniorReserveTokenAccount,
+        otcSeniorTrancheTokenAccount,
+        otcJuniorTrancheTokenAccount,
+      ])
+      .rpc();
+
+    const depositAccounts = async (isSenior: boolean) => ({
+      userReserveTokenAccount: isSenior ? userA_tokenAccount : userB_tokenAccount,
+      beneficiaryTokenAccount: isSenior ? userA_tokenAccount : userB_tokenAccount,
+      beneficiaryOwner: isSenior ? userA.publicKey : userB.publicKey,
+      otcState: otcState.publicKey,
+      otcAuthority,
+      globalConfig,
+      treasuryTokenAccount,
+      otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+      otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+      otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+      otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+      positionMint: isSenior ? seniorPositionMint : juniorPositionMint,
+      positionTokenAccount: await getPositionTokenAccount(
+        isSenior ? seniorPositionMint : juniorPositionMint,
+        isSenior ? userA.publicKey : userB.publicKey
+      ),
+
+      reserveMint,
+      seniorTrancheMint: vyperConfig.seniorTrancheMint,
+      juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+      vyperTrancheConfig: vyperConfig.trancheConfig,
+      vyperTrancheAuthority: vyperConfig.trancheAuthority,
+      vyperReserve: vyperConfig.vyperReserve,
+      vyperCore: vyperCoreProgram.programId,
+      signer: isSenior ? userA.publicKey : userB.publicKey,
+    });
+
+    // before deposit start
+    try {
+      await program.methods
+        .deposit({
+          isSeniorSide: true,
+          wrapNative: false,
+        })
+        .accounts(await depositAccounts(true))
+        .signers([userA])
+        .rpc();
+      expect(true).to.be.false;
+    } catch (err) {
+      expect(err.error.errorCode.code).to.be.eql("DepositNotOpenYet");
+    }
+
+    // inside the deposit window
+    while (Math.round(Date.now() / 1000) < depositStart + 2) {
+      await sleep(1000);
+    }
+    await program.methods
+      .deposit({
+        isSeniorSide: true,
+        wrapNative: false,
+      })
+      .accounts(await depositAccounts(true))
+      .signers([userA])
       .rpc();
+    expect(Number((await getAccount(provider.connection, otcSeniorReserveTokenAccount.publicKey)).amount)).to.be.eq(
+      seniorDepositAmount
+    );
+
+    // after deposit end
+    while (Math.round(Date.now() / 1000) < depositEnd + 2) {
+      await sleep(1000);
+    }
+    try {
+      await program.methods
+        .deposit({
+          isSeniorSide: false,
+          wrapNative: false,
+        })
+        .accounts(await depositAccounts(false))
+        .signers([userB])
+        .rpc();
+      expect(true).to.be.false;
+    } catch (err) {
+      expect(err.error.errorCode.code).to.be.eql("DepositClosed");
+    }
+  });
+
+  it("close contract from a third party refunds the creator", async () => {
+    const seniorDepositAmount = 1000;
+    const juniorDepositAmount = 1000;
+
+    const {
+      reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
+      users: [{ user: userA, tokenAccount: userA_tokenAccount }],
+    } = await createTokenAccountWrapper(provider, [seniorDepositAmount]);
+
+    await redeemLogic.initialize(5000, 1, true, true);
+
+    const rateData = anchor.web3.Keypair.generate();
+    await initializeRateMock(rateMockProgram, provider, rateData);
+
+    const otcState = anchor.web3.Keypair.generate();
+    const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
+      [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
+      program.programId
+    );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
+
+    const vyperConfig = await createVyperCoreTrancheConfig(
+      provider,
+      vyperCoreProgram,
+      reserveMint,
+      rateMockProgram.programId,
+      rateData.publicKey,
+      redeemLogic.programID,
+      redeemLogic.state,
+      otcAuthority
+    );
+
+    // accounts to create
+    const otcSeniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcSeniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+
+    const nowSeconds = Math.round(Date.now() / 1000); // current UTC timestamp in seconds
+    const depositEnd = nowSeconds + 2;
+    const settleStart = nowSeconds + 10;
+    await program.methods
+      .initialize({
+        seniorDepositAmount: new anchor.BN(seniorDepositAmount),
+        juniorDepositAmount: new anchor.BN(juniorDepositAmount),
+        depositStart: null,
+        depositEnd: new anchor.BN(depositEnd),
+        settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
+      })
+      .accounts({
+        reserveMint,
+        otcAuthority,
+        globalConfig,
+        otcState: otcState.publicKey,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperCore: vyperCoreProgram.programId,
+      })
+      .signers([
+        otcState,
+        otcSeniorReserveTokenAccount,
+        otcJuniorReserveTokenAccount,
+        otcSeniorTrancheTokenAccount,
+        otcJuniorTrancheTokenAccount,
+      ])
+      .rpc();
+
+    while (Math.round(Date.now() / 1000) < depositEnd + 3) {
+      await sleep(1000);
+    }
+
+    const creatorBalanceBefore = await provider.connection.getBalance(provider.wallet.publicKey);
+    const userABalanceBefore = await provider.connection.getBalance(userA.publicKey);
+
+    await program.methods
+      .close()
+      .accounts({
+        otcState: otcState.publicKey,
+        otcAuthority,
+        creator: provider.wallet.publicKey,
+        creatorReserveTokenAccount: treasuryTokenAccount,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        signer: userA.publicKey,
+      })
+      .signers([userA])
+      .rpc();
+
+    expect(await provider.connection.getBalance(otcState.publicKey)).to.be.eq(0);
+    expect(await provider.connection.getBalance(provider.wallet.publicKey)).to.be.gt(creatorBalanceBefore);
+    expect(await provider.connection.getBalance(userA.publicKey)).to.be.lt(userABalanceBefore);
+  });
+
+  it("deposit restricted to the designated counterparty", async () => {
+    // input data
+    const seniorDepositAmount = 1000;
+    const juniorDepositAmount = 1000;
+    const nowSeconds = Math.round(Date.now() / 1000); // current UTC timestamp in seconds
+    const depositEnd = nowSeconds + 10;
+    const settleStart = nowSeconds + 1000;
+
+    const {
+      reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
+      users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
+    } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
+
+    await redeemLogic.initialize(5000, 1, true, true);
+
+    const rateData = anchor.web3.Keypair.generate();
+    await initializeRateMock(rateMockProgram, provider, rateData);
+
+    const otcState = anchor.web3.Keypair.generate();
+    const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
+      [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
+      program.programId
+    );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
+
+    const vyperConfig = await createVyperCoreTrancheConfig(
+      provider,
+      vyperCoreProgram,
+      reserveMint,
+      rateMockProgram.programId,
+      rateData.publicKey,
+      redeemLogic.programID,
+      redeemLogic.state,
+      otcAuthority
+    );
+
+    // accounts to create
+    const otcSeniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcSeniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .initialize({
+        seniorDepositAmount: new anchor.BN(seniorDepositAmount),
+        juniorDepositAmount: new anchor.BN(juniorDepositAmount),
+        depositStart: null,
+        depositEnd: new anchor.BN(depositEnd),
+        settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: userA.publicKey,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
+      })
+      .accounts({
+        reserveMint,
+        otcAuthority,
+        globalConfig,
+        otcState: otcState.publicKey,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperCore: vyperCoreProgram.programId,
+      })
+      .signers([
+        otcState,
+        otcSeniorReserveTokenAccount,
+        otcJuniorReserveTokenAccount,
+        otcSeniorTrancheTokenAccount,
+        otcJuniorTrancheTokenAccount,
+      ])
+      .rpc();
+
+    const depositAccounts = async (user: anchor.web3.Keypair, userTokenAccount: PublicKey) => ({
+      userReserveTokenAccount: userTokenAccount,
+      beneficiaryTokenAccount: userTokenAccount,
+      beneficiaryOwner: user.publicKey,
+      otcState: otcState.publicKey,
+      otcAuthority,
+      globalConfig,
+      treasuryTokenAccount,
+      otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+      otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+      otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+      otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+      positionMint: seniorPositionMint,
+      positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, user.publicKey),
+
+      reserveMint,
+      seniorTrancheMint: vyperConfig.seniorTrancheMint,
+      juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+      vyperTrancheConfig: vyperConfig.trancheConfig,
+      vyperTrancheAuthority: vyperConfig.trancheAuthority,
+      vyperReserve: vyperConfig.vyperReserve,
+      vyperCore: vyperCoreProgram.programId,
+      signer: user.publicKey,
+    });
+
+    // senior side reserved for user A
+    try {
+      await program.methods
+        .deposit({
+          isSeniorSide: true,
+          wrapNative: false,
+        })
+        .accounts(await depositAccounts(userB, userB_tokenAccount))
+        .signers([userB])
+        .rpc();
+      expect(true).to.be.false;
+    } catch (err) {
+      expect(err.error.errorCode.code).to.be.eql("CounterpartyNotAllowed");
+    }
+
+    await program.methods
+      .deposit({
+        isSeniorSide: true,
+        wrapNative: false,
+      })
+      .accounts(await depositAccounts(userA, userA_tokenAccount))
+      .signers([userA])
+      .rpc();
+
+    const otcStateAccount = await program.account.otcState.fetchNullable(otcState.publicKey);
+    expect(otcStateAccount.seniorCounterparty.toBase58()).to.be.eql(userA.publicKey.toBase58());
+    expect(otcStateAccount.juniorCounterparty).to.be.null;
+    expect(otcStateAccount.seniorSideBeneficiary.toBase58()).to.be.eql(userA_tokenAccount.toBase58());
+  });
+
+  it("single deposit and cancel", async () => {
+    // input data
+    const seniorDepositAmount = 1000;
+    const juniorDepositAmount = 1000;
+    const nowSeconds = Math.round(Date.now() / 1000); // current UTC timestamp in seconds
+    const depositEnd = nowSeconds + 10;
+    const settleStart = nowSeconds + 20;
+
+    const {
+      reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
+      users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
+    } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
+    await redeemLogic.initialize(5000, 1, true, true);
+    const rateData = anchor.web3.Keypair.generate();
+    await initializeRateMock(rateMockProgram, provider, rateData);
+
+    const otcState = anchor.web3.Keypair.generate();
+    const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
+      [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
+      program.programId
+    );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
+
+    const vyperConfig = await createVyperCoreTrancheConfig(
+      provider,
+      vyperCoreProgram,
+      reserveMint,
+      rateMockProgram.programId,
+      rateData.publicKey,
+      redeemLogic.programID,
+      redeemLogic.state,
+      otcAuthority
+    );
+
+    // accounts to create
+    const otcSeniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcSeniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+
+    const initTx = await program.methods
+      .initialize({
+        seniorDepositAmount: new anchor.BN(seniorDepositAmount),
+        juniorDepositAmount: new anchor.BN(juniorDepositAmount),
+        depositStart: null,
+        depositEnd: new anchor.BN(depositEnd),
+        settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
+      })
+      .accounts({
+        reserveMint,
+        otcAuthority,
+        globalConfig,
+        otcState: otcState.publicKey,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperCore: vyperCoreProgram.programId,
+      })
+      .signers([
+        otcState,
+        otcSeniorReserveTokenAccount,
+        otcJuniorReserveTokenAccount,
+        otcSeniorTrancheTokenAccount,
+        otcJuniorTrancheTokenAccount,
+      ])
+      .rpc();
+    console.log("init tx: ", initTx);
+
+    const depositTx = await program.methods
+      .deposit({
+        isSeniorSide: true,
+        wrapNative: false,
+      })
+      .accounts({
+        userReserveTokenAccount: userA_tokenAccount,
+        beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
+        otcState: otcState.publicKey,
+        otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
+
+        reserveMint,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperTrancheAuthority: vyperConfig.trancheAuthority,
+        vyperReserve: vyperConfig.vyperReserve,
+        vyperCore: vyperCoreProgram.programId,
+        signer: userA.publicKey,
+      })
+      .signers([userA])
+      .rpc();
+    console.log("deposit tx: ", depositTx);
+
+    const cancelTx = await program.methods
+      .cancel()
+      .accounts({
+        otcState: otcState.publicKey,
+        otcAuthority,
+        beneficiaryTokenAccount: userA_tokenAccount,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: otcAuthority,
+        positionHolder: otcAuthority,
+        creatorReserveTokenAccount: treasuryTokenAccount,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        creator: provider.wallet.publicKey,
+      })
+      .rpc();
+    console.log("cancel tx: ", cancelTx);
+
+    expect(Number((await getAccount(provider.connection, userA_tokenAccount)).amount)).to.be.eq(seniorDepositAmount);
+    expect(await provider.connection.getBalance(otcState.publicKey)).to.be.eq(0);
+    expect(await provider.connection.getBalance(otcSeniorReserveTokenAccount.publicKey)).to.be.eq(0);
+    expect(await provider.connection.getBalance(otcJuniorReserveTokenAccount.publicKey)).to.be.eq(0);
+    expect(await provider.connection.getBalance(otcSeniorTrancheTokenAccount.publicKey)).to.be.eq(0);
+    expect(await provider.connection.getBalance(otcJuniorTrancheTokenAccount.publicKey)).to.be.eq(0);
+  });
+
+  it("update global config and rotate admin", async () => {
+    const newAdmin = anchor.web3.Keypair.generate();
+    const configInputData = (admin: PublicKey) => ({
+      admin,
+      feeBps: FEE_BPS,
+      treasury: provider.wallet.publicKey,
+      minDuration: null,
+      maxDuration: null,
+      settleGracePeriod: new anchor.BN(SETTLE_GRACE_PERIOD),
+      allowedRatePrograms: [],
+      allowedRedeemLogicPrograms: [],
+    });
+
+    await program.methods
+      .updateGlobalConfig(configInputData(newAdmin.publicKey))
+      .accounts({
+        globalConfig,
+        admin: provider.wallet.publicKey,
+      })
+      .rpc();
+    let globalConfigAccount = await program.account.globalConfig.fetch(globalConfig);
+    expect(globalConfigAccount.admin.toBase58()).to.be.eql(provider.wallet.publicKey.toBase58());
+    expect(globalConfigAccount.pendingAdmin.toBase58()).to.be.eql(newAdmin.publicKey.toBase58());
+
+    // the new admin takes over once it accepts
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        globalConfig,
+        newAdmin: newAdmin.publicKey,
+      })
+      .signers([newAdmin])
+      .rpc();
+    globalConfigAccount = await program.account.globalConfig.fetch(globalConfig);
+    expect(globalConfigAccount.admin.toBase58()).to.be.eql(newAdmin.publicKey.toBase58());
+    expect(globalConfigAccount.pendingAdmin).to.be.null;
+
+    // old admin is not allowed anymore
+    try {
+      await program.methods
+        .updateGlobalConfig(configInputData(provider.wallet.publicKey))
+        .accounts({
+          globalConfig,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc();
+      expect(true).to.be.false;
+    } catch (err) {
+      expect(err.error.errorCode.code).to.be.eql("ConstraintHasOne");
+    }
+
+    // fee above 100% is rejected
+    try {
+      await program.methods
+        .updateGlobalConfig({ ...configInputData(provider.wallet.publicKey), feeBps: 10001 })
+        .accounts({
+          globalConfig,
+          admin: newAdmin.publicKey,
+        })
+        .signers([newAdmin])
+        .rpc();
+      expect(true).to.be.false;
+    } catch (err) {
+      expect(err.error.errorCode.code).to.be.eql("InvalidGlobalConfig");
+    }
+
+    await program.methods
+      .updateGlobalConfig(configInputData(provider.wallet.publicKey))
+      .accounts({
+        globalConfig,
+        admin: newAdmin.publicKey,
+      })
+      .signers([newAdmin])
+      .rpc();
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        globalConfig,
+        newAdmin: provider.wallet.publicKey,
+      })
+      .rpc();
+    expect((await program.account.globalConfig.fetch(globalConfig)).admin.toBase58()).to.be.eql(
+      provider.wallet.publicKey.toBase58()
+    );
+  });
+
+  it("pause blocks deposits but not withdrawals", async () => {
+    // input data
+    const seniorDepositAmount = 10;
+    const juniorDepositAmount = 1000;
+    const nowSeconds = Math.round(Date.now() / 1000); // current UTC timestamp in seconds
+    const depositEnd = nowSeconds + 8;
+    const settleStart = nowSeconds + 10;
+
+    const {
+      reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
+      users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
+    } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
+
+    await redeemLogic.initialize(5000, 1, true, true);
+
+    const rateData = anchor.web3.Keypair.generate();
+    await initializeRateMock(rateMockProgram, provider, rateData);
+
+    const otcState = anchor.web3.Keypair.generate();
+    const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
+      [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
+      program.programId
+    );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
+
+    const vyperConfig = await createVyperCoreTrancheConfig(
+      provider,
+      vyperCoreProgram,
+      reserveMint,
+      rateMockProgram.programId,
+      rateData.publicKey,
+      redeemLogic.programID,
+      redeemLogic.state,
+      otcAuthority
+    );
+
+    // accounts to create
+    const otcSeniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcSeniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+
+    const initTx = await program.methods
+      .initialize({
+        seniorDepositAmount: new anchor.BN(seniorDepositAmount),
+        juniorDepositAmount: new anchor.BN(juniorDepositAmount),
+        depositStart: null,
+        depositEnd: new anchor.BN(depositEnd),
+        settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
+      })
+      .accounts({
+        reserveMint,
+        otcAuthority,
+        globalConfig,
+        otcState: otcState.publicKey,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperCore: vyperCoreProgram.programId,
+      })
+      .signers([
+        otcState,
+        otcSeniorReserveTokenAccount,
+        otcJuniorReserveTokenAccount,
+        otcSeniorTrancheTokenAccount,
+        otcJuniorTrancheTokenAccount,
+      ])
+      .rpc();
+    console.log("init tx: ", initTx);
+
+    const depositATx = await program.methods
+      .deposit({
+        isSeniorSide: true,
+        wrapNative: false,
+      })
+      .accounts({
+        userReserveTokenAccount: userA_tokenAccount,
+        beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
+        otcState: otcState.publicKey,
+        otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
+
+        reserveMint,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperTrancheAuthority: vyperConfig.trancheAuthority,
+        vyperReserve: vyperConfig.vyperReserve,
+        vyperCore: vyperCoreProgram.programId,
+        signer: userA.publicKey,
+      })
+      .signers([userA])
+      .rpc();
+    console.log("user A deposited " + seniorDepositAmount + ". tx: ", depositATx);
+
+    await program.methods
+      .setPaused(true)
+      .accounts({
+        globalConfig,
+        admin: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .deposit({
+          isSeniorSide: false,
+          wrapNative: false,
+        })
+        .accounts({
+          userReserveTokenAccount: userB_tokenAccount,
+          beneficiaryTokenAccount: userB_tokenAccount,
+          beneficiaryOwner: userB.publicKey,
+          otcState: otcState.publicKey,
+          otcAuthority,
+          globalConfig,
+          treasuryTokenAccount,
+          otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+          otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+          otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+          otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+          positionMint: juniorPositionMint,
+          positionTokenAccount: await getPositionTokenAccount(juniorPositionMint, userB.publicKey),
+
+          reserveMint,
+          seniorTrancheMint: vyperConfig.seniorTrancheMint,
+          juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+          vyperTrancheConfig: vyperConfig.trancheConfig,
+          vyperTrancheAuthority: vyperConfig.trancheAuthority,
+          vyperReserve: vyperConfig.vyperReserve,
+          vyperCore: vyperCoreProgram.programId,
+          signer: userB.publicKey,
+        })
+        .signers([userB])
+        .rpc();
+      expect(true).to.be.false;
+    } catch (err) {
+      expect(err.error.errorCode.code).to.be.eql("ProgramPaused");
+    }
+
+    const withdrawTx = await program.methods
+      .withdraw({ unwrapNative: false })
+      .accounts({
+        userReserveTokenAccount: userA_tokenAccount,
+        beneficiaryTokenAccount: userA_tokenAccount,
+        otcState: otcState.publicKey,
+        otcAuthority,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
+        reserveMint,
+        signer: userA.publicKey,
+      })
+      .signers([userA])
+      .rpc();
+    console.log("withdraw tx: ", withdrawTx);
+
+    expect(Number((await getAccount(provider.connection, userA_tokenAccount)).amount)).to.be.eq(seniorDepositAmount);
+    expect(Number((await getAccount(provider.connection, otcSeniorReserveTokenAccount.publicKey)).amount)).to.be.eq(0);
+
+    // the senior side is free again
+    const otcStateAccount = await program.account.otcState.fetchNullable(otcState.publicKey);
+    expect(otcStateAccount.seniorSideBeneficiary).to.be.null;
+    expect(otcStateAccount.juniorSideBeneficiary).to.be.null;
+
+    await program.methods
+      .setPaused(false)
+      .accounts({
+        globalConfig,
+        admin: provider.wallet.publicKey,
+      })
+      .rpc();
+    expect((await program.account.globalConfig.fetch(globalConfig)).paused).to.be.false;
+  });
+
+  it("transfer position to a new beneficiary", async () => {
+    // input data
+    const seniorDepositAmount = 1000;
+    const juniorDepositAmount = 1000;
+    const nowSeconds = Math.round(Date.now() / 1000); // current UTC timestamp in seconds
+    const depositEnd = nowSeconds + 10;
+    const settleStart = nowSeconds + 20;
+
+    const {
+      reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
+      users: [
+        { user: userA, tokenAccount: userA_tokenAccount },
+        { user: userB, tokenAccount: userB_tokenAccount },
+        { user: userC, tokenAccount: userC_tokenAccount },
+      ],
+    } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount, 0]);
+    await redeemLogic.initialize(5000, 1, true, true);
+    const rateData = anchor.web3.Keypair.generate();
+    await initializeRateMock(rateMockProgram, provider, rateData);
+
+    const otcState = anchor.web3.Keypair.generate();
+    const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
+      [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
+      program.programId
+    );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
+
+    const vyperConfig = await createVyperCoreTrancheConfig(
+      provider,
+      vyperCoreProgram,
+      reserveMint,
+      rateMockProgram.programId,
+      rateData.publicKey,
+      redeemLogic.programID,
+      redeemLogic.state,
+      otcAuthority
+    );
+
+    // accounts to create
+    const otcSeniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcSeniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+
+    const initTx = await program.methods
+      .initialize({
+        seniorDepositAmount: new anchor.BN(seniorDepositAmount),
+        juniorDepositAmount: new anchor.BN(juniorDepositAmount),
+        depositStart: null,
+        depositEnd: new anchor.BN(depositEnd),
+        settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
+      })
+      .accounts({
+        reserveMint,
+        otcAuthority,
+        globalConfig,
+        otcState: otcState.publicKey,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperCore: vyperCoreProgram.programId,
+      })
+      .signers([
+        otcState,
+        otcSeniorReserveTokenAccount,
+        otcJuniorReserveTokenAccount,
+        otcSeniorTrancheTokenAccount,
+        otcJuniorTrancheTokenAccount,
+      ])
+      .rpc();
+    console.log("init tx: ", initTx);
+
+    const depositTx = await program.methods
+      .deposit({
+        isSeniorSide: true,
+        wrapNative: false,
+      })
+      .accounts({
+        userReserveTokenAccount: userA_tokenAccount,
+        beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
+        otcState: otcState.publicKey,
+        otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
+
+        reserveMint,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperTrancheAuthority: vyperConfig.trancheAuthority,
+        vyperReserve: vyperConfig.vyperReserve,
+        vyperCore: vyperCoreProgram.programId,
+        signer: userA.publicKey,
+      })
+      .signers([userA])
+      .rpc();
+    console.log("deposit tx: ", depositTx);
+
+    const userA_positionTokenAccount = await getPositionTokenAccount(seniorPositionMint, userA.publicKey);
+    const userC_positionTokenAccount = await getPositionTokenAccount(seniorPositionMint, userC.publicKey);
+
+    // user B holds no senior position
+    try {
+      await program.methods
+        .transferPosition()
+        .accounts({
+          otcState: otcState.publicKey,
+          beneficiaryTokenAccount: userB_tokenAccount,
+          newBeneficiaryTokenAccount: userC_tokenAccount,
+          newOwner: userC.publicKey,
+          positionMint: seniorPositionMint,
+          positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userB.publicKey),
+          newPositionTokenAccount: userC_positionTokenAccount,
+          signer: userB.publicKey,
+        })
+        .signers([userB])
+        .rpc();
+      expect(true).to.be.false;
+    } catch (err) {
+      expect(err.error.errorCode.code).to.be.eql("AccountNotInitialized");
+    }
+
+    const transferTx = await program.methods
+      .transferPosition()
+      .accounts({
+        otcState: otcState.publicKey,
+        beneficiaryTokenAccount: userA_tokenAccount,
+        newBeneficiaryTokenAccount: userC_tokenAccount,
+        newOwner: userC.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: userA_positionTokenAccount,
+        newPositionTokenAccount: userC_positionTokenAccount,
+        signer: userA.publicKey,
+      })
+      .signers([userA])
+      .rpc();
+    console.log("transfer position tx: ", transferTx);
+
+    let otcStateAccount = await program.account.otcState.fetch(otcState.publicKey);
+    expect(otcStateAccount.seniorSideBeneficiary.toBase58()).to.be.eql(userC_tokenAccount.toBase58());
+    expect(Number((await getAccount(provider.connection, userA_positionTokenAccount)).amount)).to.be.eq(0);
+    expect(Number((await getAccount(provider.connection, userC_positionTokenAccount)).amount)).to.be.eq(1);
+
+    // the new beneficiary can withdraw the side
+    const withdrawTx = await program.methods
+      .withdraw({ unwrapNative: false })
+      .accounts({
+        userReserveTokenAccount: userC_tokenAccount,
+        beneficiaryTokenAccount: userC_tokenAccount,
+        otcState: otcState.publicKey,
+        otcAuthority,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: userC_positionTokenAccount,
+        reserveMint,
+        signer: userC.publicKey,
+      })
+      .signers([userC])
+      .rpc();
+    console.log("withdraw tx: ", withdrawTx);
+
+    expect(Number((await getAccount(provider.connection, userC_tokenAccount)).amount)).to.be.eq(seniorDepositAmount);
+    otcStateAccount = await program.account.otcState.fetch(otcState.publicKey);
+    expect(otcStateAccount.seniorSideBeneficiary).to.be.null;
+  });
+
+  it("update beneficiary and cancel refunds the new beneficiary", async () => {
+    // input data
+    const seniorDepositAmount = 1000;
+    const juniorDepositAmount = 1000;
+    const nowSeconds = Math.round(Date.now() / 1000); // current UTC timestamp in seconds
+    const depositEnd = nowSeconds + 10;
+    const settleStart = nowSeconds + 20;
+
+    const {
+      reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
+      users: [
+        { user: userA, tokenAccount: userA_tokenAccount },
+        { user: userB, tokenAccount: userB_tokenAccount },
+        { user: userC, tokenAccount: userC_tokenAccount },
+      ],
+    } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount, 0]);
+    await redeemLogic.initialize(5000, 1, true, true);
+    const rateData = anchor.web3.Keypair.generate();
+    await initializeRateMock(rateMockProgram, provider, rateData);
+
+    const otcState = anchor.web3.Keypair.generate();
+    const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
+      [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
+      program.programId
+    );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
+
+    const vyperConfig = await createVyperCoreTrancheConfig(
+      provider,
+      vyperCoreProgram,
+      reserveMint,
+      rateMockProgram.programId,
+      rateData.publicKey,
+      redeemLogic.programID,
+      redeemLogic.state,
+      otcAuthority
+    );
+
+    // accounts to create
+    const otcSeniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorReserveTokenAccount = anchor.web3.Keypair.generate();
+    const otcSeniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+    const otcJuniorTrancheTokenAccount = anchor.web3.Keypair.generate();
+
+    const initTx = await program.methods
+      .initialize({
+        seniorDepositAmount: new anchor.BN(seniorDepositAmount),
+        juniorDepositAmount: new anchor.BN(juniorDepositAmount),
+        depositStart: null,
+        depositEnd: new anchor.BN(depositEnd),
+        settleStart: new anchor.BN(settleStart),
+        seniorCounterparty: null,
+        juniorCounterparty: null,
+        settleDeadline: null,
+        settleBounty: new anchor.BN(0),
+      })
+      .accounts({
+        reserveMint,
+        otcAuthority,
+        globalConfig,
+        otcState: otcState.publicKey,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        seniorPositionMint,
+        juniorPositionMint,
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperCore: vyperCoreProgram.programId,
+      })
+      .signers([
+        otcState,
+        otcSeniorReserveTokenAccount,
+        otcJuniorReserveTokenAccount,
+        otcSeniorTrancheTokenAccount,
+        otcJuniorTrancheTokenAccount,
+      ])
+      .rpc();
+    console.log("init tx: ", initTx);
+
+    const depositTx = await program.methods
+      .deposit({
+        isSeniorSide: true,
+        wrapNative: false,
+      })
+      .accounts({
+        userReserveTokenAccount: userA_tokenAccount,
+        beneficiaryTokenAccount: userA_tokenAccount,
+        beneficiaryOwner: userA.publicKey,
+        otcState: otcState.publicKey,
+        otcAuthority,
+        globalConfig,
+        treasuryTokenAccount,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
+
+        reserveMint,
+        seniorTrancheMint: vyperConfig.seniorTrancheMint,
+        juniorTrancheMint: vyperConfig.juniorTrancheMint,
+
+        vyperTrancheConfig: vyperConfig.trancheConfig,
+        vyperTrancheAuthority: vyperConfig.trancheAuthority,
+        vyperReserve: vyperConfig.vyperReserve,
+        vyperCore: vyperCoreProgram.programId,
+        signer: userA.publicKey,
+      })
+      .signers([userA])
+      .rpc();
+    console.log("deposit tx: ", depositTx);
+
+    // user A lost access to the recorded beneficiary token account
+    const updateTx = await program.methods
+      .updateBeneficiary()
+      .accounts({
+        otcState: otcState.publicKey,
+        newBeneficiaryTokenAccount: userC_tokenAccount,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: await getPositionTokenAccount(seniorPositionMint, userA.publicKey),
+        signer: userA.publicKey,
+      })
+      .signers([userA])
+      .rpc();
+    console.log("update beneficiary tx: ", updateTx);
+
+    const otcStateAccount = await program.account.otcState.fetch(otcState.publicKey);
+    expect(otcStateAccount.seniorSideBeneficiary.toBase58()).to.be.eql(userC_tokenAccount.toBase58());
+
+    const cancelTx = await program.methods
+      .cancel()
+      .accounts({
+        otcState: otcState.publicKey,
+        otcAuthority,
+        beneficiaryTokenAccount: userC_tokenAccount,
+        positionMint: seniorPositionMint,
+        positionTokenAccount: otcAuthority,
+        positionHolder: otcAuthority,
+        creatorReserveTokenAccount: treasuryTokenAccount,
+        otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
+        otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
+        otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
+        otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+        creator: provider.wallet.publicKey,
+      })
+      .rpc();
+    console.log("cancel tx: ", cancelTx);
+
+    expect(Number((await getAccount(provider.connection, userC_tokenAccount)).amount)).to.be.eq(seniorDepositAmount);
+  });
+
+  it.only("close contract", async () => {
+    const seniorDepositAmount = 1000;
+    const juniorDepositAmount = 1000;
+
+    // const reserveMint = await createMint(provider);
+    const {
+      reserveMint,
+      walletTokenAccount: treasuryTokenAccount,
+      users: [{ user: userA, tokenAccount: userA_tokenAccount }, { user: userB, tokenAccount: userB_tokenAccount }],
+    } = await createTokenAccountWrapper(provider, [seniorDepositAmount, juniorDepositAmount]);
+
+    await redeemLogic.initialize(5000, 1, true, true);
+
+    const redeemLogicStateAccount = await redeemLogicVanillaOptionProgram.account.redeemLogicConfig.fetch(
+      redeemLogic.state
+    );
+    const rateData = anchor.web3.Keypair.generate();
+    await initializeRateMock(rateMockProgram, provider, rateData);
 
     const otcState = anchor.web3.Keypair.generate();
     const [otcAuthority] = await anchor.web3.PublicKey.findProgramAddress(
       [otcState.publicKey.toBuffer(), anchor.utils.bytes.utf8.encode("authority")],
       program.programId
     );
+    const { seniorPositionMint, juniorPositionMint } = await findPositionMints(program.programId, otcState.publicKey);
 
     const vyperConfig = await createVyperCoreTrancheConfig(
       provider,
       vyperCoreProgram,
       reserveMint,
-      rateSwitchboardProgram.programId,
+      rateMockProgram.programId,
       rateData.publicKey,
       redeemLogic.programID,
       redeemLogic.state,
@@ -1946,10 +3266,15 @@ describe("vyper-otc", () => {
           depositStart: null,
           depositEnd: new anchor.BN(depositEnd),
           settleStart: new anchor.BN(settleStart),
+          seniorCounterparty: null,
+          juniorCounterparty: null,
+          settleDeadline: null,
+          settleBounty: new anchor.BN(0),
         })
         .accounts({
           reserveMint,
           otcAuthority,
+          globalConfig,
           otcState: otcState.publicKey,
           seniorTrancheMint: vyperConfig.seniorTrancheMint,
           juniorTrancheMint: vyperConfig.juniorTrancheMint,
@@ -1958,6 +3283,8 @@ describe("vyper-otc", () => {
           otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
           otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,
           otcJuniorTrancheTokenAccount: otcJuniorTrancheTokenAccount.publicKey,
+          seniorPositionMint,
+          juniorPositionMint,
           vyperTrancheConfig: vyperConfig.trancheConfig,
           vyperCore: vyperCoreProgram.programId,
         })
@@ -1981,6 +3308,8 @@ describe("vyper-otc", () => {
         .accounts({
           otcState: otcState.publicKey,
           otcAuthority,
+          creator: provider.wallet.publicKey,
+          creatorReserveTokenAccount: treasuryTokenAccount,
           otcSeniorReserveTokenAccount: otcSeniorReserveTokenAccount.publicKey,
           otcJuniorReserveTokenAccount: otcJuniorReserveTokenAccount.publicKey,
           otcSeniorTrancheTokenAccount: otcSeniorTrancheTokenAccount.publicKey,